@@ -0,0 +1,130 @@
+use criterion::{
+    BenchmarkId, Criterion, Throughput, criterion_group, criterion_main,
+};
+use rand_core::RngCore;
+use sc_drbg::{Drbg, EvolutionMode};
+use sha3::Sha3_256;
+use std::{hint::black_box, io::Read};
+
+const SUBSET: usize = 4;
+
+fn get_seed_vec(elements: usize) -> Vec<Vec<u8>> {
+    (0..elements)
+        .map(|i| (0..32).map(|j| (i * 31 + j * 7) as u8).collect())
+        .collect()
+}
+
+fn bench_derivation(c: &mut Criterion) {
+    // Compare outputs of a seekable generator, which reuse the keys cached
+    // for the state, against the same outputs with the commitment, keys,
+    // and a subkey for every element recomputed on each call
+    let mut group = c.benchmark_group("subset_derivation");
+    for elements in [8, 64, 256] {
+        let arr = get_seed_vec(elements);
+        let drbg = Drbg::<Sha3_256, u64>::builder()
+            .array(&arr)
+            .context(Some("bench"))
+            .evolution(EvolutionMode::Seekable)
+            .build()
+            .expect("Should create new SC_DRBG instance");
+        group.bench_with_input(
+            BenchmarkId::new("uncached", elements),
+            &elements,
+            |b, _| {
+                let mut bytes = [0u8; 32];
+                b.iter(|| {
+                    drbg.output_at_uncached(1, SUBSET, &mut bytes)
+                        .expect("Should seek output");
+                    black_box(&bytes);
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("cached", elements),
+            &elements,
+            |b, _| {
+                let mut bytes = [0u8; 32];
+                b.iter(|| {
+                    drbg.output_at(1, SUBSET, &mut bytes)
+                        .expect("Should seek output");
+                    black_box(&bytes);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_subset(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fill_bytes_subset");
+    for elements in [8, 64, 256] {
+        let arr = get_seed_vec(elements);
+        let mut drbg = Drbg::<Sha3_256, u64>::new_le(&arr, Some("bench"), true)
+            .expect("Should create new SC_DRBG instance");
+        group.bench_with_input(
+            BenchmarkId::from_parameter(elements),
+            &elements,
+            |b, _| {
+                let mut bytes = [0u8; 32];
+                b.iter(|| {
+                    drbg.fill_bytes_subset(SUBSET, &mut bytes);
+                    black_box(&bytes);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_all(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fill_bytes");
+    for elements in [8, 64, 256] {
+        let arr = get_seed_vec(elements);
+        let mut drbg = Drbg::<Sha3_256, u64>::new_le(&arr, Some("bench"), true)
+            .expect("Should create new SC_DRBG instance");
+        group.bench_with_input(
+            BenchmarkId::from_parameter(elements),
+            &elements,
+            |b, _| {
+                let mut bytes = [0u8; 32];
+                b.iter(|| {
+                    drbg.fill_bytes(&mut bytes);
+                    black_box(&bytes);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_stream(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stream_1mib");
+    group.throughput(Throughput::Bytes(1 << 20));
+    let arr = get_seed_vec(8);
+    let mut drbg = Drbg::<Sha3_256, u64>::new_le(&arr, Some("bench"), true)
+        .expect("Should create new SC_DRBG instance");
+    for segment_len in [4096, 1 << 16, 1 << 20] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(segment_len),
+            &segment_len,
+            |b, &segment_len| {
+                let mut bytes = vec![0u8; 1 << 20];
+                b.iter(|| {
+                    let mut stream = drbg.stream(segment_len);
+                    stream.read_exact(&mut bytes).expect("Should read stream");
+                    black_box(&bytes);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_derivation,
+    bench_subset,
+    bench_all,
+    bench_stream
+);
+criterion_main!(benches);
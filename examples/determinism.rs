@@ -0,0 +1,70 @@
+//! Print a transcript of SC_DRBG outputs covering every counter width, byte
+//! order, stream backend, and a range of array and subset sizes.
+//!
+//! Used by `tests/cross_target.rs` to check that outputs are identical on 32
+//! and 64 bit hosts.
+
+use rand_core::RngCore;
+use sc_drbg::{
+    AesCtr, ChaCha20, Drbg, Shake256Xof, StreamBackend, UnsignedInt,
+};
+use sha3::Sha3_256;
+
+fn get_seed_vec(elements: usize) -> Vec<Vec<u8>> {
+    (0..elements)
+        .map(|i| (0..32).map(|j| (i * 31 + j * 7) as u8).collect())
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn transcript<T, S>(name: &str)
+where
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    // Array sizes that do not divide the integer limit of any counter width,
+    // so that rejection sampling is exercised
+    for elements in [1, 3, 7, 10, 100] {
+        let arr = get_seed_vec(elements);
+        for big_endian in [false, true] {
+            let mut drbg = if big_endian {
+                Drbg::<Sha3_256, T, S>::new_be(&arr, Some("cross-target"), true)
+            } else {
+                Drbg::<Sha3_256, T, S>::new_le(&arr, Some("cross-target"), true)
+            }
+            .expect("Should create new SC_DRBG instance");
+            let mut bytes = [0u8; 32];
+            // Every subset size, then all elements and a chosen set
+            for subset in 1..=elements.min(10) {
+                drbg.fill_bytes_subset(subset, &mut bytes);
+                println!(
+                    "{name} {elements} {big_endian} {subset} {}",
+                    hex(&bytes)
+                );
+            }
+            println!("{name} {elements} {big_endian} all {}", drbg.next_u64());
+            let chosen = [elements - 1, 0];
+            drbg.fill_bytes_with_elements(
+                &chosen[..elements.min(2)],
+                &mut bytes,
+            )
+            .expect("Should fill bytes with elements");
+            println!("{name} {elements} {big_endian} with {}", hex(&bytes));
+            drbg.reseed(&[b"additional input".to_vec()], Some("epoch-1"))
+                .expect("Should reseed SC_DRBG instance");
+            drbg.fill_bytes(&mut bytes);
+            println!("{name} {elements} {big_endian} reseed {}", hex(&bytes));
+        }
+    }
+}
+
+fn main() {
+    transcript::<u32, AesCtr>("u32-aes");
+    transcript::<u64, AesCtr>("u64-aes");
+    transcript::<u128, AesCtr>("u128-aes");
+    transcript::<u64, ChaCha20>("u64-chacha20");
+    transcript::<u64, Shake256Xof>("u64-xof");
+}
@@ -0,0 +1,280 @@
+//! Generate the known-answer test vector files under `tests/vectors/`.
+//!
+//! Run with the `vectors` feature, optionally passing the output directory:
+//! ```text
+//! cargo run --features vectors --example generate_vectors -- tests/vectors
+//! ```
+
+use sc_drbg::{Call, TestVector, VectorFile};
+use std::{env, fs, path::PathBuf};
+
+const SEED: [&str; 7] = [
+    "ca33496c5c9e5f3ce6e932a0670d320f",
+    "e17baaae2056f7cea2083482f9818b1c",
+    "2c1aef2c624598ae937eed2b5ad9448b",
+    "6932a3726327aa4a092771dabf198fc7",
+    "fe9fe0c3b16f8ae27b09856bd0f487d1",
+    "87c83f8f122b3bcccf42a97f487133f9",
+    "5bc58505a5cc3406168facc39ba0f5dc",
+];
+
+const ADDITIONAL: [&str; 2] = [
+    "9a0f6c2e4b81d3577ec1a0b2d4f68e13",
+    "52d7e8a1c3b9046f2a7d5e18b0c4f937",
+];
+
+/// Options of a vector that differ between configurations.
+struct Spec {
+    digest: &'static str,
+    counter: &'static str,
+    endian: &'static str,
+    context: Option<&'static str>,
+    init: bool,
+    nonce: Option<&'static str>,
+    rounds: usize,
+    seekable: bool,
+}
+
+fn calls() -> Vec<Call> {
+    // Calls covering every operation, subset sizes, and output lengths
+    let expected = String::new;
+    vec![
+        Call::NextU32 {
+            subset: None,
+            expected: expected(),
+        },
+        Call::NextU32 {
+            subset: None,
+            expected: expected(),
+        },
+        Call::NextU64 {
+            subset: Some(1),
+            expected: expected(),
+        },
+        Call::NextU64 {
+            subset: Some(3),
+            expected: expected(),
+        },
+        Call::FillBytes {
+            subset: Some(2),
+            len: 48,
+            expected: expected(),
+        },
+        Call::FillBytes {
+            subset: None,
+            len: 100,
+            expected: expected(),
+        },
+        Call::FillBytesWithElements {
+            indices: vec![6, 0, 3],
+            len: 32,
+            expected: expected(),
+        },
+        Call::Reseed {
+            additional: ADDITIONAL.iter().map(|s| s.to_string()).collect(),
+            label: Some("epoch-1".to_string()),
+        },
+        Call::NextU64 {
+            subset: None,
+            expected: expected(),
+        },
+        Call::FillBytes {
+            subset: Some(4),
+            len: 16,
+            expected: expected(),
+        },
+        Call::FillBytesWithInput {
+            subset: Some(5),
+            input: "757365722d3132333435".to_string(),
+            len: 40,
+            expected: expected(),
+        },
+        Call::FillBytesWithInput {
+            subset: None,
+            input: String::new(),
+            len: 24,
+            expected: expected(),
+        },
+        Call::Fork {
+            label: "worker-0".to_string(),
+            len: 32,
+            expected: expected(),
+        },
+        Call::Fork {
+            label: "worker-0".to_string(),
+            len: 32,
+            expected: expected(),
+        },
+        Call::FillBytes {
+            subset: None,
+            len: 32,
+            expected: expected(),
+        },
+    ]
+}
+
+fn seekable_calls() -> Vec<Call> {
+    // Sequential calls, followed by seeks reproducing them and jumping ahead,
+    // then the same within the next ratchet
+    let expected = String::new;
+    vec![
+        Call::FillBytes {
+            subset: Some(3),
+            len: 32,
+            expected: expected(),
+        },
+        Call::NextU64 {
+            subset: None,
+            expected: expected(),
+        },
+        Call::FillBytes {
+            subset: None,
+            len: 48,
+            expected: expected(),
+        },
+        Call::OutputAt {
+            counter: 0,
+            subset: Some(3),
+            len: 32,
+            expected: expected(),
+        },
+        Call::OutputAt {
+            counter: 2,
+            subset: None,
+            len: 48,
+            expected: expected(),
+        },
+        Call::OutputAt {
+            counter: 10000,
+            subset: Some(2),
+            len: 16,
+            expected: expected(),
+        },
+        Call::Ratchet,
+        Call::FillBytes {
+            subset: None,
+            len: 32,
+            expected: expected(),
+        },
+        Call::OutputAt {
+            counter: 0,
+            subset: None,
+            len: 32,
+            expected: expected(),
+        },
+    ]
+}
+
+fn vector(spec: &Spec) -> TestVector {
+    let name = format!(
+        "{}-{}-{}{}{}",
+        spec.digest.to_lowercase(),
+        spec.counter,
+        spec.endian.to_lowercase(),
+        if spec.init { "" } else { "-no-init" },
+        if spec.seekable { "-seekable" } else { "" }
+    );
+    TestVector {
+        name,
+        digest: spec.digest.to_string(),
+        counter: spec.counter.to_string(),
+        endian: spec.endian.to_string(),
+        seed: SEED.iter().map(|s| s.to_string()).collect(),
+        context: spec.context.map(|c| c.to_string()),
+        init: spec.init,
+        nonce: spec.nonce.map(|n| n.to_string()),
+        rounds: spec.rounds,
+        seekable: spec.seekable,
+        calls: if spec.seekable {
+            seekable_calls()
+        } else {
+            calls()
+        },
+    }
+}
+
+fn spec(
+    digest: &'static str,
+    counter: &'static str,
+    endian: &'static str,
+) -> Spec {
+    Spec {
+        digest,
+        counter,
+        endian,
+        context: Some("some-test-app"),
+        init: true,
+        nonce: None,
+        rounds: 1,
+        seekable: false,
+    }
+}
+
+fn main() {
+    let dir = env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("tests/vectors"));
+    // SHA-3 family, every counter width and byte order for SHA3-256
+    let sha3 = vec![
+        spec("SHA3-256", "u32", "LE"),
+        spec("SHA3-256", "u32", "BE"),
+        spec("SHA3-256", "u64", "LE"),
+        spec("SHA3-256", "u64", "BE"),
+        spec("SHA3-256", "u128", "LE"),
+        spec("SHA3-256", "u128", "BE"),
+        Spec {
+            init: false,
+            ..spec("SHA3-224", "u32", "LE")
+        },
+        Spec {
+            context: None,
+            ..spec("SHA3-384", "u128", "BE")
+        },
+        Spec {
+            nonce: Some("74656e616e742d30303432"),
+            rounds: 3,
+            ..spec("SHA3-512", "u64", "BE")
+        },
+        Spec {
+            seekable: true,
+            ..spec("SHA3-256", "u64", "LE")
+        },
+        Spec {
+            seekable: true,
+            ..spec("SHA3-256", "u32", "BE")
+        },
+    ];
+    // SHA-2 family
+    let sha2 = vec![
+        spec("SHA-224", "u32", "BE"),
+        spec("SHA-256", "u64", "LE"),
+        Spec {
+            init: false,
+            ..spec("SHA-384", "u64", "BE")
+        },
+        Spec {
+            nonce: Some("74656e616e742d30303432"),
+            rounds: 3,
+            ..spec("SHA-512", "u128", "LE")
+        },
+        Spec {
+            seekable: true,
+            ..spec("SHA-256", "u64", "BE")
+        },
+    ];
+    for (file, description, specs) in [
+        ("sha3.json", "SC_DRBG vectors for the SHA-3 family", sha3),
+        ("sha2.json", "SC_DRBG vectors for the SHA-2 family", sha2),
+    ] {
+        let mut vectors = VectorFile {
+            description: description.to_string(),
+            vectors: specs.iter().map(vector).collect(),
+        };
+        vectors.generate().expect("Should generate vectors");
+        let path = dir.join(file);
+        fs::write(&path, vectors.to_json() + "\n")
+            .expect("Should write vector file");
+        println!("wrote {}", path.display());
+    }
+}
@@ -0,0 +1,387 @@
+use crate::{
+    Drbg, DrbgBuilder, DrbgError, Endian,
+    backend::{AesCtr, StreamBackend},
+    traits::UnsignedInt,
+};
+use alloc::{vec, vec::Vec};
+use digest::{
+    Digest, HashMarker, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+use rand_core::{CryptoRng, RngCore};
+
+const TRANSCRIPT_LABEL: &[u8] = b"SC_DRBG-TRANSCRIPT";
+const OUTPUT_LABEL: &[u8] = b"SC_DRBG-OUTPUT";
+
+/// Record of one call made on an [AuditedDrbg].
+///
+/// Entries are hash-chained: each `chain` value is a hash of the previous
+/// `chain` value and every other field of the entry, so that entries cannot
+/// be altered, removed, or reordered without breaking the chain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TranscriptEntry {
+    /// Counter value used for the call.
+    pub counter: u128,
+    /// Number of elements the output was generated from.
+    pub subset: usize,
+    /// Whether the elements were selected explicitly, rather than by the PRF.
+    pub explicit: bool,
+    /// Indices of the elements the output was generated from, in order of
+    /// selection.
+    pub indices: Vec<usize>,
+    /// Number of bytes generated.
+    pub len: usize,
+    /// Commitment to the array of seed material before the call.
+    pub commit_before: Vec<u8>,
+    /// Commitment to the array of seed material after the call.
+    pub commit_after: Vec<u8>,
+    /// Hash of the generated bytes.
+    pub output_hash: Vec<u8>,
+    /// Hash chaining the entry to the previous entries of the transcript.
+    pub chain: Vec<u8>,
+}
+
+/// Wrapper around a [Drbg] that records a transcript of every call.
+///
+/// Intended for regulated draws, such as lotteries or jury selection, where
+/// it must later be shown which calls produced which outputs. For each call,
+/// the transcript records the counter, the subset size, the selected element
+/// indices, the state commitment before and after the call, and a hash of the
+/// output, as a [TranscriptEntry]. The transcript can be replayed from the
+/// original configuration and array of seed material with
+/// [verify_transcript].
+///
+/// Outputs are identical to those of the wrapped [Drbg]. Reseeding is not
+/// supported, as the transcript could no longer be replayed from the
+/// original seed material alone.
+///
+/// # Example
+/// ```
+/// use hex_literal::hex;
+/// use rand_core::RngCore;
+/// use sc_drbg::{AuditedDrbg, Drbg, verify_transcript};
+/// use sha3::Sha3_256;
+///
+/// let arr = vec![
+///     hex!("456E64204F662054686520576F726C642053756E").to_vec(),
+///     hex!("556E6D616B65207468652057696C64204C69676874").to_vec(),
+///     hex!("536166652050617373616765").to_vec(),
+/// ];
+/// let builder = Drbg::<Sha3_256, u64>::builder()
+///     .array(&arr)
+///     .context(Some("jury-selection-2026"));
+///
+/// let mut drbg = AuditedDrbg::new(&builder)
+///     .expect("Should create new SC_DRBG instance");
+/// let draw = drbg.next_u32_subset(2);
+///
+/// let transcript = drbg.into_transcript();
+/// assert_eq!(transcript[0].subset, 2);
+/// assert!(verify_transcript(&builder, &transcript).is_ok());
+/// ```
+pub struct AuditedDrbg<D, T, S = AesCtr>
+where
+    D: OutputSizeUser,
+{
+    drbg: Drbg<D, T, S>,
+    transcript: Vec<TranscriptEntry>,
+}
+
+impl<D, T, S> AuditedDrbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Create a new [AuditedDrbg] instance from the options of a
+    /// [DrbgBuilder], with an empty transcript.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if the builder fails to create a [Drbg]. See
+    /// [DrbgBuilder::build].
+    pub fn new(builder: &DrbgBuilder<D, T, S>) -> Result<Self, DrbgError> {
+        Ok(Self {
+            drbg: builder.build()?,
+            transcript: Vec::new(),
+        })
+    }
+    /// Return the wrapped [Drbg].
+    pub fn drbg(&self) -> &Drbg<D, T, S> {
+        &self.drbg
+    }
+    /// Return the transcript of calls recorded so far.
+    pub fn transcript(&self) -> &[TranscriptEntry] {
+        &self.transcript
+    }
+    /// Consume the [AuditedDrbg], returning the transcript of calls.
+    pub fn into_transcript(self) -> Vec<TranscriptEntry> {
+        self.transcript
+    }
+    /// Return the next random `u32`, seeded by a subset of elements, and
+    /// record the call. See [Drbg::next_u32_subset].
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [AuditedDrbg::try_next_u32_subset] to handle counter exhaustion as an
+    /// error.
+    pub fn next_u32_subset(&mut self, subset: usize) -> u32 {
+        self.try_next_u32_subset(subset)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Return the next random `u64`, seeded by a subset of elements, and
+    /// record the call. See [Drbg::next_u64_subset].
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [AuditedDrbg::try_next_u64_subset] to handle counter exhaustion as an
+    /// error.
+    pub fn next_u64_subset(&mut self, subset: usize) -> u64 {
+        self.try_next_u64_subset(subset)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Fills a destination buffer with random bytes, seeded by a subset of
+    /// elements, and record the call. See [Drbg::fill_bytes_subset].
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [AuditedDrbg::try_fill_bytes_subset] to handle counter exhaustion as
+    /// an error.
+    pub fn fill_bytes_subset(&mut self, subset: usize, dst: &mut [u8]) {
+        self.try_fill_bytes_subset(subset, dst)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Fallible variant of [AuditedDrbg::next_u32_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. Nothing is recorded on error.
+    pub fn try_next_u32_subset(
+        &mut self,
+        subset: usize,
+    ) -> Result<u32, DrbgError> {
+        let mut bytes = [0u8; 4];
+        self.try_fill_bytes_subset(subset, &mut bytes)?;
+        match self.drbg.endian {
+            Endian::LittleEndian => Ok(u32::from_le_bytes(bytes)),
+            Endian::BigEndian => Ok(u32::from_be_bytes(bytes)),
+        }
+    }
+    /// Fallible variant of [AuditedDrbg::next_u64_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. Nothing is recorded on error.
+    pub fn try_next_u64_subset(
+        &mut self,
+        subset: usize,
+    ) -> Result<u64, DrbgError> {
+        let mut bytes = [0u8; 8];
+        self.try_fill_bytes_subset(subset, &mut bytes)?;
+        match self.drbg.endian {
+            Endian::LittleEndian => Ok(u64::from_le_bytes(bytes)),
+            Endian::BigEndian => Ok(u64::from_be_bytes(bytes)),
+        }
+    }
+    /// Fallible variant of [AuditedDrbg::fill_bytes_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. Nothing is recorded on error.
+    pub fn try_fill_bytes_subset(
+        &mut self,
+        subset: usize,
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.record(None, subset, dst)
+    }
+    /// Fills a destination buffer with random bytes, seeded by an explicit
+    /// set of elements, and record the call. See
+    /// [Drbg::fill_bytes_with_elements].
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if the indices are invalid, or if the counter has
+    /// reached its maximum value. Nothing is recorded on error.
+    pub fn fill_bytes_with_elements(
+        &mut self,
+        indices: &[usize],
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.record(Some(indices), indices.len(), dst)
+    }
+    fn record(
+        &mut self,
+        indices: Option<&[usize]>,
+        subset: usize,
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        // Record the state before the call
+        let counter = self.drbg.ctr.as_u128();
+        let commit_before = self.drbg.commitment_root();
+        // Generate output from the given or PRF selected elements
+        let (explicit, indices) = match indices {
+            Some(indices) => {
+                self.drbg.fill_bytes_with_elements(indices, dst)?;
+                (true, indices.to_vec())
+            }
+            None => {
+                let selected =
+                    self.drbg.fill_bytes_selected(subset, &[], dst)?;
+                (false, selected.iter().map(|i| i.as_usize()).collect())
+            }
+        };
+        // Hash the output, and chain the entry to the previous entry
+        let mut entry = TranscriptEntry {
+            counter,
+            subset: indices.len(),
+            explicit,
+            indices,
+            len: dst.len(),
+            commit_before,
+            commit_after: self.drbg.commitment_root(),
+            output_hash: D::new()
+                .chain_update(OUTPUT_LABEL)
+                .chain_update(&*dst)
+                .finalize()
+                .to_vec(),
+            chain: Vec::new(),
+        };
+        entry.chain = Self::chain(self.transcript.last(), &entry);
+        self.transcript.push(entry);
+        Ok(())
+    }
+    fn chain(
+        previous: Option<&TranscriptEntry>,
+        entry: &TranscriptEntry,
+    ) -> Vec<u8> {
+        // Start from the previous chain value, or a hash of the label for the
+        // first entry
+        let mut hasher = D::new();
+        match previous {
+            Some(previous) => hasher.update(&previous.chain),
+            None => hasher.update(D::digest(TRANSCRIPT_LABEL)),
+        }
+        // Absorb every other field of the entry, with fixed width integers
+        // and length prefixes
+        hasher.update(entry.counter.to_be_bytes());
+        hasher.update([entry.explicit as u8]);
+        for value in [entry.subset, entry.len, entry.indices.len()] {
+            hasher.update((value as u64).to_be_bytes());
+        }
+        for &i in &entry.indices {
+            hasher.update((i as u64).to_be_bytes());
+        }
+        for field in [&entry.commit_before, &entry.commit_after] {
+            hasher.update((field.len() as u64).to_be_bytes());
+            hasher.update(field);
+        }
+        hasher.update((entry.output_hash.len() as u64).to_be_bytes());
+        hasher.update(&entry.output_hash);
+        hasher.finalize().to_vec()
+    }
+}
+
+/// Verify a transcript recorded by an [AuditedDrbg], by replaying its calls.
+///
+/// A new [AuditedDrbg] is created from the options of the builder, which must
+/// hold the original array of seed material and configuration, and every
+/// call in the transcript is made again. The transcript is valid if every
+/// replayed entry, including its hash chain, matches the recorded entry.
+///
+/// # Arguments
+/// - `builder` - Builder with the original options of the [AuditedDrbg].
+/// - `transcript` - Transcript to verify.
+///
+/// # Errors
+/// Returns [DrbgError::TranscriptMismatch] for the first entry that does not
+/// match its replay, or a [DrbgError] if the builder fails to create a
+/// [Drbg].
+pub fn verify_transcript<D, T, S>(
+    builder: &DrbgBuilder<D, T, S>,
+    transcript: &[TranscriptEntry],
+) -> Result<(), DrbgError>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    let mut audited = AuditedDrbg::new(builder)?;
+    for (i, entry) in transcript.iter().enumerate() {
+        // Replay the call, which must succeed and record an identical entry
+        let mut dst = vec![0u8; entry.len];
+        let indices = entry.explicit.then_some(&entry.indices[..]);
+        let replayed = audited.record(indices, entry.subset, &mut dst);
+        if replayed.is_err() || audited.transcript.last() != Some(entry) {
+            return Err(DrbgError::TranscriptMismatch { entry: i });
+        }
+    }
+    Ok(())
+}
+
+impl<D, T, S> RngCore for AuditedDrbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    fn next_u32(&mut self) -> u32 {
+        self.next_u32_subset(self.drbg.arr.len())
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.next_u64_subset(self.drbg.arr.len())
+    }
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.fill_bytes_subset(self.drbg.arr.len(), dst);
+    }
+}
+
+impl<D, T, S> CryptoRng for AuditedDrbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+}
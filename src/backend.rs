@@ -0,0 +1,167 @@
+use aes::{
+    Aes128, Aes192, Aes256,
+    cipher::{KeyIvInit, StreamCipher},
+};
+use alloc::vec::Vec;
+use chacha20::ChaCha20 as ChaCha20Cipher;
+use ctr::Ctr128BE;
+use digest::{ExtendableOutput, Update, XofReader, core_api::CoreWrapper};
+use sha3::{CShake256, CShake256Core};
+
+/// A trait for output backends used to generate SC_DRBG output.
+///
+/// After the selected elements are finalized, a PRF key and nonce are derived
+/// from the accumulator, commitment, and counter. The backend fills the
+/// destination buffer from that key and nonce, producing the output bytes.
+/// Stream cipher backends apply their keystream to a zeroed buffer, while
+/// hash-based backends may also absorb the accumulator and context. The
+/// backend is selected at the type level via the `S` generic parameter of
+/// `Drbg`.
+pub trait StreamBackend {
+    /// Name of the backend, used to identify it in state snapshots.
+    const NAME: &'static str;
+    /// Length of the nonce in bytes. Must not exceed 16.
+    const NONCE_LEN: usize;
+    /// Return the key length in bytes, for a given digest output size. Must
+    /// not exceed 64.
+    fn key_len(digest_len: usize) -> usize;
+    /// Fill a buffer with output for a PRF key, nonce, accumulator, and
+    /// context string.
+    fn fill(
+        key: &[u8],
+        nonce: &[u8],
+        acc: &[u8],
+        context: &str,
+        dst: &mut [u8],
+    );
+}
+
+/// AES in counter mode, with a 128 bit big-endian counter.
+///
+/// The AES variant is chosen by the digest output size: AES-256 for digests
+/// of at least 32 bytes, AES-192 for at least 24 bytes, and AES-128
+/// otherwise. This is the default backend.
+pub struct AesCtr;
+
+impl StreamBackend for AesCtr {
+    const NAME: &'static str = "AES-CTR";
+    const NONCE_LEN: usize = 16;
+    fn key_len(digest_len: usize) -> usize {
+        if digest_len >= 32 {
+            32 // AES-256
+        } else if digest_len >= 24 {
+            24 // AES-192
+        } else {
+            16 // AES-128
+        }
+    }
+    fn fill(
+        key: &[u8],
+        nonce: &[u8],
+        _acc: &[u8],
+        _context: &str,
+        dst: &mut [u8],
+    ) {
+        // Fill the destination buffer with zero bytes
+        dst.fill(0);
+        // Encrypt zero bytes, change AES variant based on key size
+        match key.len() {
+            16 => {
+                let mut cipher =
+                    Ctr128BE::<Aes128>::new(key.into(), nonce.into());
+                cipher.apply_keystream(dst);
+            }
+            24 => {
+                let mut cipher =
+                    Ctr128BE::<Aes192>::new(key.into(), nonce.into());
+                cipher.apply_keystream(dst);
+            }
+            32 => {
+                let mut cipher =
+                    Ctr128BE::<Aes256>::new(key.into(), nonce.into());
+                cipher.apply_keystream(dst);
+            }
+            _ => panic!("key length {} is invalid for AES-CTR", key.len()),
+        }
+    }
+}
+
+/// The ChaCha20 stream cipher, with a 96 bit nonce.
+///
+/// Always uses a 256 bit key, expanded from the digest output with HKDF
+/// under the label `ChaCha20_KEY_EXPANSION` when it is shorter than 32 bytes.
+/// Suited to platforms without AES instructions, where ChaCha20 is faster and
+/// free of table lookups.
+///
+/// # Panics
+/// ChaCha20 uses a 32 bit block counter, so a single output is limited to
+/// 256 GiB.
+pub struct ChaCha20;
+
+impl StreamBackend for ChaCha20 {
+    const NAME: &'static str = "ChaCha20";
+    const NONCE_LEN: usize = 12;
+    fn key_len(_digest_len: usize) -> usize {
+        32
+    }
+    fn fill(
+        key: &[u8],
+        nonce: &[u8],
+        _acc: &[u8],
+        _context: &str,
+        dst: &mut [u8],
+    ) {
+        // Fill the destination buffer with zero bytes, then encrypt them
+        dst.fill(0);
+        let mut cipher = ChaCha20Cipher::new(key.into(), nonce.into());
+        cipher.apply_keystream(dst);
+    }
+}
+
+/// A pure hash construction using cSHAKE256 as an extendable output function.
+///
+/// The PRF key, nonce, and accumulator are absorbed into cSHAKE256, customized
+/// with the context string, and the output is squeezed into the destination
+/// buffer. Uses the full digest output as the key. Suited to environments
+/// where a block cipher cannot be used.
+pub struct Shake256Xof;
+
+const XOF_SUFFIX: &[u8] = b"-XOF";
+const XOF_STACK_LEN: usize = 256;
+
+impl StreamBackend for Shake256Xof {
+    const NAME: &'static str = "cSHAKE256";
+    const NONCE_LEN: usize = 16;
+    fn key_len(digest_len: usize) -> usize {
+        digest_len
+    }
+    fn fill(
+        key: &[u8],
+        nonce: &[u8],
+        acc: &[u8],
+        context: &str,
+        dst: &mut [u8],
+    ) {
+        // Customize cSHAKE256 with the context, assembled on the stack unless
+        // the context is too long
+        let len = context.len() + XOF_SUFFIX.len();
+        let mut stack = [0u8; XOF_STACK_LEN];
+        let mut heap = Vec::new();
+        let customization = if len <= XOF_STACK_LEN {
+            &mut stack[..len]
+        } else {
+            heap.resize(len, 0);
+            &mut heap[..]
+        };
+        customization[..context.len()].copy_from_slice(context.as_bytes());
+        customization[context.len()..].copy_from_slice(XOF_SUFFIX);
+        let mut xof: CShake256 =
+            CoreWrapper::from_core(CShake256Core::new(customization));
+        // Absorb PRF key, nonce, and accumulator
+        xof.update(key);
+        xof.update(nonce);
+        xof.update(acc);
+        // Squeeze output into the destination buffer
+        xof.finalize_xof().read(dst);
+    }
+}
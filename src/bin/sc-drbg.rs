@@ -0,0 +1,229 @@
+//! Command-line tool to generate bytes and integers with SC_DRBG from a file
+//! of seed elements.
+//!
+//! Built with the `cli` feature:
+//! ```text
+//! cargo run --features cli --bin sc-drbg -- seeds.txt --context app -n 32
+//! ```
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use clap::{Parser, ValueEnum};
+use sc_drbg::{Algorithm, CounterWidth, DigestAlgorithm, DynDrbg, Endian};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    process::ExitCode,
+};
+
+/// Generate bytes and integers with SC_DRBG from a file of seed elements.
+#[derive(Parser)]
+#[command(name = "sc-drbg", version)]
+struct Args {
+    /// File of seed elements, one hex string per line or a JSON array of hex
+    /// strings. Reads from stdin when `-`.
+    seeds: String,
+    /// Context string for domain separation.
+    #[arg(long)]
+    context: Option<String>,
+    /// Hashing algorithm from the SHA-2 or SHA-3 family, such as `sha3-256`,
+    /// `sha256`, or `sha512`.
+    #[arg(long, default_value = "sha3-256")]
+    digest: DigestAlgorithm,
+    /// Integer type of the internal counter: `u32`, `u64`, or `u128`.
+    #[arg(long, default_value = "u64")]
+    counter: CounterWidth,
+    /// Byte order used to encode integers.
+    #[arg(long, value_enum, default_value_t = EndianArg::Le)]
+    endian: EndianArg,
+    /// Number of elements to seed each output with. Defaults to all.
+    #[arg(long)]
+    subset: Option<usize>,
+    /// Apply the initialization function to the seed elements.
+    #[arg(long)]
+    init: bool,
+    /// Kind of output to generate.
+    #[arg(long, value_enum, default_value_t = EmitArg::Bytes)]
+    emit: EmitArg,
+    /// Number of bytes or integers to generate.
+    #[arg(short = 'n', long, default_value_t = 32)]
+    count: usize,
+    /// Output encoding. Integers are encoded in big-endian byte order for
+    /// `raw` and `base64`, and one per line for `hex` and `dec`.
+    #[arg(long, value_enum, default_value_t = FormatArg::Hex)]
+    format: FormatArg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum EndianArg {
+    Le,
+    Be,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum EmitArg {
+    Bytes,
+    U32,
+    U64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FormatArg {
+    Hex,
+    Dec,
+    Raw,
+    Base64,
+}
+
+/// Generated output, either a byte string or a list of integers.
+enum Output {
+    Bytes(Vec<u8>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("sc-drbg: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    // Read and parse seed elements
+    let input = if args.seeds == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|err| format!("failed to read stdin: {}", err))?;
+        input
+    } else {
+        fs::read_to_string(&args.seeds)
+            .map_err(|err| format!("failed to read {}: {}", args.seeds, err))?
+    };
+    let arr = parse_seeds(&input)?;
+    // Generate output with the selected algorithm
+    let endian = match args.endian {
+        EndianArg::Le => Endian::LittleEndian,
+        EndianArg::Be => Endian::BigEndian,
+    };
+    let algorithm = Algorithm::new(args.digest, args.counter, endian);
+    let output = generate(algorithm, &arr, args)?;
+    // Encode and write output
+    let encoded = encode(output, args.format)?;
+    io::stdout()
+        .write_all(&encoded)
+        .map_err(|err| format!("failed to write output: {}", err))
+}
+
+/// Parse seed elements from a JSON array of hex strings, or from hex lines.
+///
+/// Blank lines and lines starting with `#` are ignored in the line format.
+fn parse_seeds(input: &str) -> Result<Vec<Vec<u8>>, String> {
+    let lines: Vec<(usize, String)> = if input.trim_start().starts_with('[') {
+        let values: Vec<String> = serde_json::from_str(input)
+            .map_err(|err| format!("invalid JSON seed file: {}", err))?;
+        values.into_iter().enumerate().collect()
+    } else {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line.trim().to_string()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .collect()
+    };
+    lines
+        .iter()
+        .map(|(i, line)| {
+            decode_hex(line)
+                .ok_or_else(|| format!("invalid hex seed element {}", i + 1))
+        })
+        .collect()
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    // Check every character, as from_str_radix accepts a leading sign
+    if !s.len().is_multiple_of(2) || !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn generate(
+    algorithm: Algorithm,
+    arr: &[Vec<u8>],
+    args: &Args,
+) -> Result<Output, String> {
+    // Create DRBG with the selected algorithm
+    let context = args.context.as_deref();
+    let mut drbg = DynDrbg::new(algorithm, arr, context, args.init)
+        .map_err(|err| err.to_string())?;
+    // Generate bytes or integers seeded by the subset of elements
+    let subset = args.subset.unwrap_or(arr.len());
+    let output = match args.emit {
+        EmitArg::Bytes => {
+            let mut bytes = vec![0u8; args.count];
+            drbg.try_fill_bytes_subset(subset, &mut bytes)
+                .map_err(|err| err.to_string())?;
+            Output::Bytes(bytes)
+        }
+        EmitArg::U32 => Output::U32(
+            (0..args.count)
+                .map(|_| drbg.try_next_u32_subset(subset))
+                .collect::<Result<_, _>>()
+                .map_err(|err| err.to_string())?,
+        ),
+        EmitArg::U64 => Output::U64(
+            (0..args.count)
+                .map(|_| drbg.try_next_u64_subset(subset))
+                .collect::<Result<_, _>>()
+                .map_err(|err| err.to_string())?,
+        ),
+    };
+    Ok(output)
+}
+
+fn encode(output: Output, format: FormatArg) -> Result<Vec<u8>, String> {
+    // Integers are encoded big-endian for byte-oriented formats
+    let bytes = match &output {
+        Output::Bytes(bytes) => bytes.clone(),
+        Output::U32(nums) => {
+            nums.iter().flat_map(|n| n.to_be_bytes()).collect()
+        }
+        Output::U64(nums) => {
+            nums.iter().flat_map(|n| n.to_be_bytes()).collect()
+        }
+    };
+    let text = match (format, &output) {
+        (FormatArg::Raw, _) => return Ok(bytes),
+        (FormatArg::Base64, _) => STANDARD.encode(&bytes),
+        (FormatArg::Hex, Output::Bytes(bytes)) => hex(bytes),
+        (FormatArg::Hex, Output::U32(nums)) => {
+            lines(nums, |n| format!("{:08x}", n))
+        }
+        (FormatArg::Hex, Output::U64(nums)) => {
+            lines(nums, |n| format!("{:016x}", n))
+        }
+        (FormatArg::Dec, Output::Bytes(_)) => {
+            return Err("dec format requires --emit u32 or u64".to_string());
+        }
+        (FormatArg::Dec, Output::U32(nums)) => lines(nums, |n| n.to_string()),
+        (FormatArg::Dec, Output::U64(nums)) => lines(nums, |n| n.to_string()),
+    };
+    Ok(format!("{}\n", text).into_bytes())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn lines<N>(nums: &[N], f: impl Fn(&N) -> String) -> String {
+    nums.iter().map(f).collect::<Vec<_>>().join("\n")
+}
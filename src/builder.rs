@@ -0,0 +1,274 @@
+use crate::{
+    CommitmentMode, Drbg, DrbgError, Endian, EvolutionMode,
+    backend::{AesCtr, StreamBackend},
+    traits::UnsignedInt,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+use digest::{
+    Digest, HashMarker, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Builder for configuring and creating a [Drbg] instance.
+///
+/// Exposes all options used when creating a new instance from an array of
+/// seed material: the context string, initialization nonce, number of mixing
+/// rounds, byte order, commitment mode, evolution mode, and whether the seed
+/// material is initialized at all. Options that are not set fall back to the
+/// same defaults used by [Drbg::new_le] and [Drbg::new_be].
+///
+/// # Defaults
+/// - `context` - No context string.
+/// - `nonce` - A hash of the concatenated array of seed material.
+/// - `rounds` - One round of mixing.
+/// - `endian` - Little-endian byte order.
+/// - `commitment` - [CommitmentMode::Flat].
+/// - `evolution` - [EvolutionMode::Sequential].
+/// - `init` - Seed material is processed by [Drbg::initialize].
+///
+/// # Example
+/// ```
+/// use hex_literal::hex;
+/// use rand_core::RngCore;
+/// use sc_drbg::{Drbg, Endian};
+/// use sha3::Sha3_256;
+///
+/// let arr = vec![
+///     hex!("456E64204F662054686520576F726C642053756E").to_vec(),
+///     hex!("556E6D616B65207468652057696C64204C69676874").to_vec(),
+///     hex!("536166652050617373616765").to_vec(),
+/// ];
+///
+/// let mut drbg = Drbg::<Sha3_256, u64>::builder()
+///     .array(&arr)
+///     .context(Some("some-random-application"))
+///     .nonce(b"tenant-0042")
+///     .rounds(3)
+///     .endian(Endian::BigEndian)
+///     .build()
+///     .expect("Should create new SC_DRBG instance");
+///
+/// let num = drbg.next_u64();
+/// ```
+pub struct DrbgBuilder<D, T, S = AesCtr> {
+    arr: Vec<Vec<u8>>,
+    layout: Option<(usize, usize)>,
+    context: Option<String>,
+    nonce: Option<Vec<u8>>,
+    rounds: usize,
+    endian: Endian,
+    commitment: CommitmentMode,
+    evolution: EvolutionMode,
+    init: bool,
+    _marker: PhantomData<(D, T, S)>,
+}
+
+impl<D, T, S> DrbgBuilder<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Create a new [DrbgBuilder] with all options set to their defaults.
+    pub fn new() -> Self {
+        Self {
+            arr: Vec::new(),
+            layout: None,
+            context: None,
+            nonce: None,
+            rounds: 1,
+            endian: Endian::LittleEndian,
+            commitment: CommitmentMode::Flat,
+            evolution: EvolutionMode::Sequential,
+            init: true,
+            _marker: PhantomData,
+        }
+    }
+    /// Set the array of seed material.
+    pub fn array(mut self, arr: &[Vec<u8>]) -> Self {
+        self.arr.zeroize();
+        self.layout = None;
+        self.arr = arr.to_vec();
+        self
+    }
+    /// Set the array of seed material by splitting a seed into elements.
+    ///
+    /// The seed is split into `elements` consecutive elements of equal
+    /// length, in order. This allows a single fixed-size seed to be used as
+    /// an array of seed material.
+    ///
+    /// # Arguments
+    /// - `seed` - Seed to split into elements.
+    /// - `elements` - Number of elements to split the seed into. Must evenly
+    ///   divide the seed length, otherwise [DrbgBuilder::build] returns an
+    ///   error.
+    pub fn seed(mut self, seed: &[u8], elements: usize) -> Self {
+        self.arr.zeroize();
+        self.layout = Some((seed.len(), elements));
+        if elements > 0 && seed.len().is_multiple_of(elements) {
+            let element_len = seed.len() / elements;
+            self.arr = seed.chunks(element_len.max(1)).map(Vec::from).collect();
+        }
+        self
+    }
+    /// Set the optional context string used for domain separation.
+    pub fn context(mut self, context: Option<&str>) -> Self {
+        self.context = context.map(|c| c.to_string());
+        self
+    }
+    /// Set the nonce used to derive the initialization keys.
+    ///
+    /// Only used when the seed material is initialized. When not set, a hash
+    /// of the concatenated array of seed material is used as the nonce. An
+    /// empty nonce is rejected by [DrbgBuilder::build].
+    pub fn nonce(mut self, nonce: &[u8]) -> Self {
+        self.nonce.zeroize();
+        self.nonce = Some(nonce.to_vec());
+        self
+    }
+    /// Set the number of mixing rounds applied during initialization.
+    ///
+    /// Only used when the seed material is initialized. More rounds provide
+    /// increased diffusion at a higher computational cost. At least one round
+    /// is required, as zero rounds would skip mixing entirely, and zero is
+    /// rejected by [DrbgBuilder::build].
+    pub fn rounds(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+    /// Set the byte order used for integer encoding and decoding.
+    pub fn endian(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+    /// Set the scheme used to commit to the array of seed material.
+    ///
+    /// [CommitmentMode::Merkle] enables element inclusion proofs with
+    /// [Drbg::prove_element], and changes the output of the generator.
+    pub fn commitment(mut self, commitment: CommitmentMode) -> Self {
+        self.commitment = commitment;
+        self
+    }
+    /// Set the scheme used to evolve the state between outputs.
+    ///
+    /// [EvolutionMode::Seekable] enables random access to outputs with
+    /// [Drbg::output_at], at the cost of forward secrecy between calls to
+    /// [Drbg::ratchet]. See [EvolutionMode] for the security trade-off.
+    pub fn evolution(mut self, evolution: EvolutionMode) -> Self {
+        self.evolution = evolution;
+        self
+    }
+    /// Set whether the seed material is processed by [Drbg::initialize]
+    /// before creating the new instance.
+    pub fn init(mut self, init: bool) -> Self {
+        self.init = init;
+        self
+    }
+    /// Create a new [Drbg] instance from the configured options.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if the array of seed material is empty or has
+    /// empty elements, if a seed cannot be split into the requested number of
+    /// elements, or if the digest size of `D` is below 16 bytes. Returns
+    /// [DrbgError::InvalidRounds] if the number of mixing rounds is zero, or
+    /// [DrbgError::EmptyNonce] if the nonce is set to an empty value.
+    pub fn build(&self) -> Result<Drbg<D, T, S>, DrbgError> {
+        if let Some((len, elements)) = self.layout
+            && (elements == 0 || !len.is_multiple_of(elements))
+        {
+            return Err(DrbgError::InvalidSeedLayout { len, elements });
+        }
+        if self.rounds == 0 {
+            return Err(DrbgError::InvalidRounds);
+        }
+        if self.nonce.as_ref().is_some_and(|nonce| nonce.is_empty()) {
+            return Err(DrbgError::EmptyNonce);
+        }
+        Drbg::<D, T, S>::validate_array(&self.arr)?;
+        Drbg::<D, T, S>::validate_digest()?;
+        let context = self.context.as_deref();
+        if self.init {
+            let nonce = Zeroizing::new(match &self.nonce {
+                Some(nonce) => nonce.clone(),
+                None => {
+                    let mut hasher = D::new();
+                    for element in &self.arr {
+                        hasher.update(element);
+                    }
+                    hasher.finalize().to_vec()
+                }
+            });
+            let arr_init = Zeroizing::new(Drbg::<D, T, S>::initialize(
+                &self.arr,
+                context,
+                nonce.to_vec(),
+                self.rounds,
+                self.endian,
+            ));
+            Ok(Drbg::new_from(
+                &arr_init,
+                context,
+                self.endian,
+                self.commitment,
+                self.evolution,
+            ))
+        } else {
+            Ok(Drbg::new_from(
+                &self.arr,
+                context,
+                self.endian,
+                self.commitment,
+                self.evolution,
+            ))
+        }
+    }
+}
+
+impl<D, T, S> Default for DrbgBuilder<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, T, S> Drop for DrbgBuilder<D, T, S> {
+    fn drop(&mut self) {
+        self.arr.zeroize();
+        self.nonce.zeroize();
+    }
+}
+
+impl<D, T, S> ZeroizeOnDrop for DrbgBuilder<D, T, S> {}
@@ -0,0 +1,676 @@
+use crate::{Drbg, DrbgError, Endian, EvolutionMode};
+use alloc::{string::ToString, vec::Vec};
+use core::{fmt, str::FromStr};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Sha224, Sha256, Sha384, Sha512};
+use sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+
+/// Hashing algorithm selectable at runtime by a [DynDrbg].
+///
+/// Parsed case-insensitively from its name, such as `SHA3-256` or `SHA-256`.
+/// The hyphen in the names of the SHA-2 family is optional.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DigestAlgorithm {
+    /// SHA-224 from the SHA-2 family.
+    Sha224,
+    /// SHA-256 from the SHA-2 family.
+    Sha256,
+    /// SHA-384 from the SHA-2 family.
+    Sha384,
+    /// SHA-512 from the SHA-2 family.
+    Sha512,
+    /// SHA3-224 from the SHA-3 family.
+    Sha3_224,
+    /// SHA3-256 from the SHA-3 family.
+    Sha3_256,
+    /// SHA3-384 from the SHA-3 family.
+    Sha3_384,
+    /// SHA3-512 from the SHA-3 family.
+    Sha3_512,
+}
+
+/// Width of the internal counter selectable at runtime by a [DynDrbg].
+///
+/// Parsed case-insensitively from the name of the integer type, such as
+/// `u64`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CounterWidth {
+    /// 32 bit counter, using `u32`.
+    U32,
+    /// 64 bit counter, using `u64`.
+    U64,
+    /// 128 bit counter, using `u128`.
+    U128,
+}
+
+/// Descriptor of the hashing algorithm, counter width, and byte order used by
+/// a [DynDrbg].
+///
+/// Parsed from and displayed as a string of the form
+/// `<digest>/<counter>/<endian>`, such as `SHA3-256/u64/BE`, where the byte
+/// order is either `LE` or `BE`. Parsing is case-insensitive.
+///
+/// # Example
+/// ```
+/// use sc_drbg::{Algorithm, CounterWidth, DigestAlgorithm, Endian};
+///
+/// let algorithm: Algorithm = "SHA3-256/u64/BE"
+///     .parse()
+///     .expect("Should parse algorithm descriptor");
+///
+/// assert_eq!(algorithm.digest, DigestAlgorithm::Sha3_256);
+/// assert_eq!(algorithm.counter, CounterWidth::U64);
+/// assert_eq!(algorithm.endian, Endian::BigEndian);
+/// assert_eq!(algorithm.to_string(), "SHA3-256/u64/BE");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Algorithm {
+    /// Hashing algorithm.
+    pub digest: DigestAlgorithm,
+    /// Width of the internal counter.
+    pub counter: CounterWidth,
+    /// Byte order for integer encoding and decoding.
+    pub endian: Endian,
+}
+
+impl Algorithm {
+    /// Create a new algorithm descriptor.
+    ///
+    /// # Arguments
+    /// - `digest` - Hashing algorithm.
+    /// - `counter` - Width of the internal counter.
+    /// - `endian` - Byte order for integer encoding and decoding.
+    pub fn new(
+        digest: DigestAlgorithm,
+        counter: CounterWidth,
+        endian: Endian,
+    ) -> Self {
+        Self {
+            digest,
+            counter,
+            endian,
+        }
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DigestAlgorithm::Sha224 => "SHA-224",
+            DigestAlgorithm::Sha256 => "SHA-256",
+            DigestAlgorithm::Sha384 => "SHA-384",
+            DigestAlgorithm::Sha512 => "SHA-512",
+            DigestAlgorithm::Sha3_224 => "SHA3-224",
+            DigestAlgorithm::Sha3_256 => "SHA3-256",
+            DigestAlgorithm::Sha3_384 => "SHA3-384",
+            DigestAlgorithm::Sha3_512 => "SHA3-512",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = DrbgError;
+
+    fn from_str(s: &str) -> Result<Self, DrbgError> {
+        match s.to_ascii_uppercase().as_str() {
+            "SHA-224" | "SHA224" => Ok(DigestAlgorithm::Sha224),
+            "SHA-256" | "SHA256" => Ok(DigestAlgorithm::Sha256),
+            "SHA-384" | "SHA384" => Ok(DigestAlgorithm::Sha384),
+            "SHA-512" | "SHA512" => Ok(DigestAlgorithm::Sha512),
+            "SHA3-224" => Ok(DigestAlgorithm::Sha3_224),
+            "SHA3-256" => Ok(DigestAlgorithm::Sha3_256),
+            "SHA3-384" => Ok(DigestAlgorithm::Sha3_384),
+            "SHA3-512" => Ok(DigestAlgorithm::Sha3_512),
+            _ => Err(DrbgError::InvalidAlgorithm(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for CounterWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CounterWidth::U32 => "u32",
+            CounterWidth::U64 => "u64",
+            CounterWidth::U128 => "u128",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for CounterWidth {
+    type Err = DrbgError;
+
+    fn from_str(s: &str) -> Result<Self, DrbgError> {
+        match s.to_ascii_lowercase().as_str() {
+            "u32" => Ok(CounterWidth::U32),
+            "u64" => Ok(CounterWidth::U64),
+            "u128" => Ok(CounterWidth::U128),
+            _ => Err(DrbgError::InvalidAlgorithm(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let endian = match self.endian {
+            Endian::LittleEndian => "LE",
+            Endian::BigEndian => "BE",
+        };
+        write!(f, "{}/{}/{}", self.digest, self.counter, endian)
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = DrbgError;
+
+    fn from_str(s: &str) -> Result<Self, DrbgError> {
+        let invalid = || DrbgError::InvalidAlgorithm(s.to_string());
+        // Split descriptor into digest, counter, and byte order
+        let mut parts = s.split('/');
+        let (Some(digest), Some(counter), Some(endian), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let endian = match endian.to_ascii_uppercase().as_str() {
+            "LE" => Endian::LittleEndian,
+            "BE" => Endian::BigEndian,
+            _ => return Err(invalid()),
+        };
+        Ok(Self {
+            digest: digest.parse().map_err(|_| invalid())?,
+            counter: counter.parse().map_err(|_| invalid())?,
+            endian,
+        })
+    }
+}
+
+/// Declare the inner enum of [DynDrbg] with one variant per combination of
+/// hashing algorithm and counter width, along with methods dispatching to the
+/// wrapped [Drbg].
+macro_rules! dyn_drbg {
+    ($($variant:ident => $digest:ident, $counter:ident($t:ty);)*) => {
+        enum Inner {
+            $($variant(Drbg<$digest, $t>),)*
+        }
+
+        impl Inner {
+            fn new(
+                algorithm: Algorithm,
+                arr: &[Vec<u8>],
+                context: Option<&str>,
+                nonce: Option<&[u8]>,
+                rounds: usize,
+                init: bool,
+                evolution: EvolutionMode,
+            ) -> Result<Self, DrbgError> {
+                match (algorithm.digest, algorithm.counter) {
+                    $((DigestAlgorithm::$digest, CounterWidth::$counter) => {
+                        let mut builder = Drbg::<$digest, $t>::builder()
+                            .array(arr)
+                            .context(context)
+                            .rounds(rounds)
+                            .endian(algorithm.endian)
+                            .evolution(evolution)
+                            .init(init);
+                        if let Some(nonce) = nonce {
+                            builder = builder.nonce(nonce);
+                        }
+                        Ok(Inner::$variant(builder.build()?))
+                    })*
+                }
+            }
+            fn try_next_u32_subset(
+                &mut self,
+                subset: usize,
+            ) -> Result<u32, DrbgError> {
+                match self {
+                    $(Inner::$variant(drbg) => {
+                        drbg.try_next_u32_subset(subset)
+                    })*
+                }
+            }
+            fn try_next_u64_subset(
+                &mut self,
+                subset: usize,
+            ) -> Result<u64, DrbgError> {
+                match self {
+                    $(Inner::$variant(drbg) => {
+                        drbg.try_next_u64_subset(subset)
+                    })*
+                }
+            }
+            fn try_fill_bytes_subset(
+                &mut self,
+                subset: usize,
+                dst: &mut [u8],
+            ) -> Result<(), DrbgError> {
+                match self {
+                    $(Inner::$variant(drbg) => {
+                        drbg.try_fill_bytes_subset(subset, dst)
+                    })*
+                }
+            }
+            fn fill_bytes_with_input(
+                &mut self,
+                subset: usize,
+                additional_input: &[u8],
+                dst: &mut [u8],
+            ) -> Result<(), DrbgError> {
+                match self {
+                    $(Inner::$variant(drbg) => {
+                        drbg.fill_bytes_with_input(
+                            subset,
+                            additional_input,
+                            dst,
+                        )
+                    })*
+                }
+            }
+            fn fill_bytes_with_elements(
+                &mut self,
+                indices: &[usize],
+                dst: &mut [u8],
+            ) -> Result<(), DrbgError> {
+                match self {
+                    $(Inner::$variant(drbg) => {
+                        drbg.fill_bytes_with_elements(indices, dst)
+                    })*
+                }
+            }
+            fn reseed(
+                &mut self,
+                additional: &[Vec<u8>],
+                label: Option<&str>,
+            ) -> Result<(), DrbgError> {
+                match self {
+                    $(Inner::$variant(drbg) => drbg.reseed(additional, label),)*
+                }
+            }
+            fn output_at(
+                &self,
+                counter: u128,
+                subset: usize,
+                dst: &mut [u8],
+            ) -> Result<(), DrbgError> {
+                match self {
+                    $(Inner::$variant(drbg) => {
+                        // Counters beyond the width are never reached
+                        let counter = <$t>::try_from(counter).map_err(|_| {
+                            DrbgError::CounterExhausted {
+                                max: <$t>::MAX.into(),
+                            }
+                        })?;
+                        drbg.output_at(counter, subset, dst)
+                    })*
+                }
+            }
+            fn ratchet(&mut self) {
+                match self {
+                    $(Inner::$variant(drbg) => drbg.ratchet(),)*
+                }
+            }
+            fn fork(&mut self, label: &str) -> Self {
+                match self {
+                    $(Inner::$variant(drbg) => {
+                        Inner::$variant(drbg.fork(label))
+                    })*
+                }
+            }
+            fn len(&self) -> usize {
+                match self {
+                    $(Inner::$variant(drbg) => drbg.arr.len(),)*
+                }
+            }
+        }
+    };
+}
+
+dyn_drbg! {
+    Sha224U32 => Sha224, U32(u32);
+    Sha224U64 => Sha224, U64(u64);
+    Sha224U128 => Sha224, U128(u128);
+    Sha256U32 => Sha256, U32(u32);
+    Sha256U64 => Sha256, U64(u64);
+    Sha256U128 => Sha256, U128(u128);
+    Sha384U32 => Sha384, U32(u32);
+    Sha384U64 => Sha384, U64(u64);
+    Sha384U128 => Sha384, U128(u128);
+    Sha512U32 => Sha512, U32(u32);
+    Sha512U64 => Sha512, U64(u64);
+    Sha512U128 => Sha512, U128(u128);
+    Sha3_224U32 => Sha3_224, U32(u32);
+    Sha3_224U64 => Sha3_224, U64(u64);
+    Sha3_224U128 => Sha3_224, U128(u128);
+    Sha3_256U32 => Sha3_256, U32(u32);
+    Sha3_256U64 => Sha3_256, U64(u64);
+    Sha3_256U128 => Sha3_256, U128(u128);
+    Sha3_384U32 => Sha3_384, U32(u32);
+    Sha3_384U64 => Sha3_384, U64(u64);
+    Sha3_384U128 => Sha3_384, U128(u128);
+    Sha3_512U32 => Sha3_512, U32(u32);
+    Sha3_512U64 => Sha3_512, U64(u64);
+    Sha3_512U128 => Sha3_512, U128(u128);
+}
+
+/// SC_DRBG with the hashing algorithm, counter width, and byte order selected
+/// at runtime.
+///
+/// Wraps a [Drbg] using the [AesCtr](crate::AesCtr) stream backend for each
+/// supported combination of hashing algorithm and counter width, dispatching
+/// through an enum without heap allocation of the generator. A `DynDrbg`
+/// produces output identical to the equivalent statically typed [Drbg].
+///
+/// # Example
+/// ```
+/// use hex_literal::hex;
+/// use rand_core::RngCore;
+/// use sc_drbg::{Algorithm, DynDrbg};
+///
+/// let arr = vec![
+///     hex!("456E64204F662054686520576F726C642053756E").to_vec(),
+///     hex!("556E6D616B65207468652057696C64204C69676874").to_vec(),
+///     hex!("536166652050617373616765").to_vec(),
+/// ];
+///
+/// let algorithm: Algorithm = "SHA-512/u64/LE"
+///     .parse()
+///     .expect("Should parse algorithm descriptor");
+/// let mut drbg =
+///     DynDrbg::new(algorithm, &arr, Some("some-random-application"), true)
+///         .expect("Should create new SC_DRBG instance");
+///
+/// let num = drbg.next_u32_subset(2);
+/// ```
+pub struct DynDrbg {
+    inner: Inner,
+    algorithm: Algorithm,
+}
+
+impl DynDrbg {
+    /// Create a new [DynDrbg] from an array of seed material.
+    ///
+    /// # Arguments
+    /// - `algorithm` - Hashing algorithm, counter width, and byte order.
+    /// - `arr` - Array of seed material.
+    /// - `context` - Optional context string for domain separation.
+    /// - `init` - Process seed material with the initialization function.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] under the same conditions as [Drbg::new_le] and
+    /// [Drbg::new_be].
+    pub fn new(
+        algorithm: Algorithm,
+        arr: &[Vec<u8>],
+        context: Option<&str>,
+        init: bool,
+    ) -> Result<Self, DrbgError> {
+        Self::with_options(
+            algorithm,
+            arr,
+            context,
+            None,
+            1,
+            init,
+            EvolutionMode::Sequential,
+        )
+    }
+    /// Create a new seekable [DynDrbg] from an array of seed material, using
+    /// [EvolutionMode::Seekable].
+    ///
+    /// See [EvolutionMode] for the security trade-off of seekable generators.
+    ///
+    /// # Arguments
+    /// - `algorithm` - Hashing algorithm, counter width, and byte order.
+    /// - `arr` - Array of seed material.
+    /// - `context` - Optional context string for domain separation.
+    /// - `init` - Process seed material with the initialization function.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] under the same conditions as [DynDrbg::new].
+    pub fn new_seekable(
+        algorithm: Algorithm,
+        arr: &[Vec<u8>],
+        context: Option<&str>,
+        init: bool,
+    ) -> Result<Self, DrbgError> {
+        Self::with_options(
+            algorithm,
+            arr,
+            context,
+            None,
+            1,
+            init,
+            EvolutionMode::Seekable,
+        )
+    }
+    /// Create a new [DynDrbg] with an explicit initialization nonce, number
+    /// of mixing rounds, and evolution mode, matching the options of
+    /// [DrbgBuilder](crate::DrbgBuilder).
+    pub(crate) fn with_options(
+        algorithm: Algorithm,
+        arr: &[Vec<u8>],
+        context: Option<&str>,
+        nonce: Option<&[u8]>,
+        rounds: usize,
+        init: bool,
+        evolution: EvolutionMode,
+    ) -> Result<Self, DrbgError> {
+        let inner = Inner::new(
+            algorithm, arr, context, nonce, rounds, init, evolution,
+        )?;
+        Ok(Self { inner, algorithm })
+    }
+    /// Return the algorithm descriptor of the [DynDrbg].
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+    /// Return the next random `u32`, seeded by a subset of elements from the
+    /// [DynDrbg] state.
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [DynDrbg::try_next_u32_subset] to handle counter exhaustion as an
+    /// error.
+    pub fn next_u32_subset(&mut self, subset: usize) -> u32 {
+        self.try_next_u32_subset(subset)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Return the next random `u64`, seeded by a subset of elements from the
+    /// [DynDrbg] state.
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [DynDrbg::try_next_u64_subset] to handle counter exhaustion as an
+    /// error.
+    pub fn next_u64_subset(&mut self, subset: usize) -> u64 {
+        self.try_next_u64_subset(subset)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Fill a destination buffer with random bytes, seeded by a subset of
+    /// elements from the [DynDrbg] state.
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [DynDrbg::try_fill_bytes_subset] to handle counter exhaustion as an
+    /// error.
+    pub fn fill_bytes_subset(&mut self, subset: usize, dst: &mut [u8]) {
+        self.try_fill_bytes_subset(subset, dst)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Return the next random `u32`, seeded by a subset of elements from the
+    /// [DynDrbg] state.
+    ///
+    /// Fallible variant of [DynDrbg::next_u32_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value.
+    pub fn try_next_u32_subset(
+        &mut self,
+        subset: usize,
+    ) -> Result<u32, DrbgError> {
+        self.inner.try_next_u32_subset(subset)
+    }
+    /// Return the next random `u64`, seeded by a subset of elements from the
+    /// [DynDrbg] state.
+    ///
+    /// Fallible variant of [DynDrbg::next_u64_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value.
+    pub fn try_next_u64_subset(
+        &mut self,
+        subset: usize,
+    ) -> Result<u64, DrbgError> {
+        self.inner.try_next_u64_subset(subset)
+    }
+    /// Fill a destination buffer with random bytes, seeded by a subset of
+    /// elements from the [DynDrbg] state.
+    ///
+    /// Fallible variant of [DynDrbg::fill_bytes_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value.
+    pub fn try_fill_bytes_subset(
+        &mut self,
+        subset: usize,
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.inner.try_fill_bytes_subset(subset, dst)
+    }
+    /// Fill a destination buffer with random bytes, seeded by a subset of
+    /// elements from the [DynDrbg] state and bound to additional input.
+    ///
+    /// See [Drbg::fill_bytes_with_input].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value.
+    pub fn fill_bytes_with_input(
+        &mut self,
+        subset: usize,
+        additional_input: &[u8],
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.inner
+            .fill_bytes_with_input(subset, additional_input, dst)
+    }
+    /// Fill a destination buffer with random bytes, seeded by an explicit set
+    /// of elements from the [DynDrbg] state.
+    ///
+    /// See [Drbg::fill_bytes_with_elements].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::EmptySelection], [DrbgError::IndexOutOfRange], or
+    /// [DrbgError::DuplicateIndex] if the indices are invalid, or
+    /// [DrbgError::CounterExhausted] if the counter has reached its maximum
+    /// value.
+    pub fn fill_bytes_with_elements(
+        &mut self,
+        indices: &[usize],
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.inner.fill_bytes_with_elements(indices, dst)
+    }
+    /// Reseed the [DynDrbg] state with additional seed material.
+    ///
+    /// See [Drbg::reseed].
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if the array of additional seed material is
+    /// empty or has empty elements.
+    pub fn reseed(
+        &mut self,
+        additional: &[Vec<u8>],
+        label: Option<&str>,
+    ) -> Result<(), DrbgError> {
+        self.inner.reseed(additional, label)
+    }
+    /// Fill a destination buffer with the output at a given counter value,
+    /// seeded by a subset of elements from the [DynDrbg] state.
+    ///
+    /// See [Drbg::output_at].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::SeekUnavailable] if the [DynDrbg] was not created
+    /// with [DynDrbg::new_seekable], or [DrbgError::CounterExhausted] if
+    /// `counter` is not below the maximum value of the counter.
+    pub fn output_at(
+        &self,
+        counter: u128,
+        subset: usize,
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.inner.output_at(counter, subset, dst)
+    }
+    /// Ratchet the [DynDrbg] state forward, for forward secrecy.
+    ///
+    /// See [Drbg::ratchet].
+    pub fn ratchet(&mut self) {
+        self.inner.ratchet()
+    }
+    /// Fork an independent child generator from the [DynDrbg] state.
+    ///
+    /// See [Drbg::fork].
+    ///
+    /// # Arguments
+    /// - `label` - Label for domain separation between children.
+    pub fn fork(&mut self, label: &str) -> DynDrbg {
+        DynDrbg {
+            inner: self.inner.fork(label),
+            algorithm: self.algorithm,
+        }
+    }
+    /// Split the [DynDrbg] into `n` independent child generators.
+    ///
+    /// See [Drbg::split_n].
+    ///
+    /// # Arguments
+    /// - `n` - Number of child generators.
+    pub fn split_n(&mut self, n: usize) -> Vec<DynDrbg> {
+        (0..n).map(|i| self.fork(&i.to_string())).collect()
+    }
+}
+
+impl RngCore for DynDrbg {
+    /// Return the next random `u32`.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value.
+    fn next_u32(&mut self) -> u32 {
+        self.next_u32_subset(self.inner.len())
+    }
+    /// Return the next random `u64`.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value.
+    fn next_u64(&mut self) -> u64 {
+        self.next_u64_subset(self.inner.len())
+    }
+    /// Fill a destination buffer with random bytes.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value.
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.fill_bytes_subset(self.inner.len(), dst)
+    }
+}
+
+impl CryptoRng for DynDrbg {}
@@ -1,7 +1,8 @@
+use alloc::{string::String, vec::Vec};
 use core::fmt;
 
 /// Enum that represents all possible errors that can be returned by `Drbg`
-/// constructors.
+/// constructors and fallible methods.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DrbgError {
     /// Error that is returned when an empty array is received instead of an
@@ -13,6 +14,124 @@ pub enum DrbgError {
     /// Error that is returned when the selected hash algorithm's digest size
     /// is below 16 bytes.
     DigestTooSmall(usize),
+    /// Error that is returned when a seed cannot be split into the requested
+    /// number of equal length elements.
+    InvalidSeedLayout {
+        /// Length of the seed in bytes.
+        len: usize,
+        /// Requested number of elements.
+        elements: usize,
+    },
+    /// Error that is returned when zero mixing rounds are requested for
+    /// initialization.
+    InvalidRounds,
+    /// Error that is returned when an explicit initialization nonce is empty.
+    EmptyNonce,
+    /// Error that is returned when elements do not have the length required
+    /// by a fixed-capacity generator.
+    InvalidElementLength {
+        /// Required element length in bytes.
+        expected: usize,
+        /// Element length in bytes that was received.
+        found: usize,
+    },
+    /// Error that is returned when a context string exceeds the maximum length
+    /// supported by a fixed-capacity generator.
+    ContextTooLong {
+        /// Length of the context string in bytes.
+        len: usize,
+        /// Maximum supported length in bytes.
+        max: usize,
+    },
+    /// Error that is returned when selected element indices are outside the
+    /// bounds of the array of seed material.
+    IndexOutOfRange(Vec<usize>),
+    /// Error that is returned when an element index is selected more than
+    /// once.
+    DuplicateIndex(Vec<usize>),
+    /// Error that is returned when an explicit set of elements to seed an
+    /// output is empty.
+    EmptySelection,
+    /// Error that is returned when the minimum number of elements to select
+    /// for a quorum generator is zero, or exceeds the number of elements.
+    InvalidThreshold {
+        /// Requested minimum number of elements.
+        threshold: usize,
+        /// Number of elements in the array of seed material.
+        count: usize,
+    },
+    /// Error that is returned when fewer elements are selected than the
+    /// minimum required by a quorum generator.
+    BelowThreshold {
+        /// Minimum number of elements to select.
+        threshold: usize,
+        /// Number of elements that were selected.
+        found: usize,
+    },
+    /// Error that is returned when selected elements are not held by a
+    /// quorum generator, or were not verified against its commitment.
+    MissingElements(Vec<usize>),
+    /// Error that is returned when the counter has reached its maximum value,
+    /// and no further output can be generated.
+    CounterExhausted {
+        /// Maximum value of the counter.
+        max: u128,
+    },
+    /// Error that is returned when a state snapshot is malformed.
+    InvalidState,
+    /// Error that is returned when a state snapshot uses an unsupported
+    /// format version.
+    UnsupportedStateVersion(u8),
+    /// Error that is returned when a state snapshot was exported with a
+    /// different hashing algorithm.
+    DigestMismatch,
+    /// Error that is returned when a state snapshot was exported with a
+    /// different stream backend.
+    BackendMismatch,
+    /// Error that is returned when a state snapshot was exported with a
+    /// different counter width.
+    CounterMismatch {
+        /// Expected counter width in bytes.
+        expected: usize,
+        /// Counter width in bytes recorded in the state snapshot.
+        found: usize,
+    },
+    /// Error that is returned when an element inclusion proof is requested
+    /// from a generator that does not use the Merkle commitment mode.
+    ProofUnavailable,
+    /// Error that is returned when a commitment does not have the digest
+    /// length of the selected hash algorithm.
+    InvalidCommitment {
+        /// Digest length in bytes.
+        expected: usize,
+        /// Commitment length in bytes that was received.
+        found: usize,
+    },
+    /// Error that is returned when an output is requested at a given counter
+    /// value from a generator that does not use the seekable evolution mode.
+    SeekUnavailable,
+    /// Error that is returned when an entry of an audit transcript does not
+    /// match its replay.
+    TranscriptMismatch {
+        /// Index of the entry within the transcript.
+        entry: usize,
+    },
+    /// Error that is returned when an algorithm descriptor cannot be parsed.
+    InvalidAlgorithm(String),
+    /// Error that is returned when a test vector is malformed.
+    InvalidVector(String),
+    /// Error that is returned when the output of a test vector call does not
+    /// match the expected output.
+    VectorMismatch {
+        /// Name of the test vector.
+        name: String,
+        /// Index of the call within the test vector.
+        call: usize,
+        /// Expected hex encoded output.
+        expected: String,
+        /// Hex encoded output that was generated.
+        found: String,
+    },
 }
 
 impl fmt::Display for DrbgError {
@@ -39,8 +158,143 @@ impl fmt::Display for DrbgError {
                     size
                 )
             }
+            DrbgError::InvalidSeedLayout { len, elements } => {
+                write!(
+                    f,
+                    "Seed of {} bytes cannot be split into {} equal elements",
+                    len, elements
+                )
+            }
+            DrbgError::InvalidRounds => {
+                write!(f, "At least one mixing round is required")
+            }
+            DrbgError::EmptyNonce => {
+                write!(f, "Nonce cannot be empty")
+            }
+            DrbgError::InvalidElementLength { expected, found } => {
+                write!(
+                    f,
+                    "Element length {} bytes does not match required {} bytes",
+                    found, expected
+                )
+            }
+            DrbgError::ContextTooLong { len, max } => {
+                write!(
+                    f,
+                    "Context of {} bytes exceeds maximum {} bytes",
+                    len, max
+                )
+            }
+            DrbgError::IndexOutOfRange(indices) => {
+                if indices.len() == 1 {
+                    write!(f, "Element index {} is out of range", indices[0])
+                } else {
+                    write!(f, "Element indices {:?} are out of range", indices)
+                }
+            }
+            DrbgError::DuplicateIndex(indices) => {
+                if indices.len() == 1 {
+                    write!(f, "Element index {} is duplicated", indices[0])
+                } else {
+                    write!(f, "Element indices {:?} are duplicated", indices)
+                }
+            }
+            DrbgError::EmptySelection => {
+                write!(f, "At least one element must be selected")
+            }
+            DrbgError::InvalidThreshold { threshold, count } => {
+                write!(
+                    f,
+                    "Threshold {} must be between 1 and the {} elements",
+                    threshold, count
+                )
+            }
+            DrbgError::BelowThreshold { threshold, found } => {
+                write!(
+                    f,
+                    "Selected {} elements is below threshold {}",
+                    found, threshold
+                )
+            }
+            DrbgError::MissingElements(indices) => {
+                if indices.len() == 1 {
+                    write!(f, "Element at index {} is missing", indices[0])
+                } else {
+                    write!(f, "Elements at indices {:?} are missing", indices)
+                }
+            }
+            DrbgError::CounterExhausted { max } => {
+                write!(f, "Counter exhausted at its maximum value {}", max)
+            }
+            DrbgError::InvalidState => {
+                write!(f, "State snapshot is malformed")
+            }
+            DrbgError::UnsupportedStateVersion(version) => {
+                write!(f, "State snapshot version {} is not supported", version)
+            }
+            DrbgError::DigestMismatch => {
+                write!(f, "State snapshot was exported with a different digest")
+            }
+            DrbgError::BackendMismatch => {
+                write!(
+                    f,
+                    "State snapshot was exported with a different backend"
+                )
+            }
+            DrbgError::CounterMismatch { expected, found } => {
+                write!(
+                    f,
+                    "State snapshot counter width {} bytes does not match {} \
+                     bytes",
+                    found, expected
+                )
+            }
+            DrbgError::ProofUnavailable => {
+                write!(f, "Element proofs require the Merkle commitment mode")
+            }
+            DrbgError::InvalidCommitment { expected, found } => {
+                write!(
+                    f,
+                    "Commitment length {} bytes does not match digest length \
+                     {} bytes",
+                    found, expected
+                )
+            }
+            DrbgError::SeekUnavailable => {
+                write!(f, "Random access requires the seekable evolution mode")
+            }
+            DrbgError::TranscriptMismatch { entry } => {
+                write!(
+                    f,
+                    "Transcript entry {} does not match its replay",
+                    entry
+                )
+            }
+            DrbgError::InvalidAlgorithm(descriptor) => {
+                write!(
+                    f,
+                    "Algorithm descriptor {:?} is not supported",
+                    descriptor
+                )
+            }
+            DrbgError::InvalidVector(reason) => {
+                write!(f, "Test vector is malformed: {}", reason)
+            }
+            DrbgError::VectorMismatch {
+                name,
+                call,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "Test vector {:?} call {} expected {} but found {}",
+                    name, call, expected, found
+                )
+            }
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for DrbgError {}
@@ -0,0 +1,542 @@
+use crate::{
+    CommitmentMode, Drbg, DrbgError, Endian,
+    backend::{AesCtr, StreamBackend},
+    prf::{Prf, StateKeys},
+    traits::UnsignedInt,
+};
+use core::marker::PhantomData;
+use digest::{
+    Digest, HashMarker, Output, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+use rand_core::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A fixed-capacity, heap-free variant of [Drbg].
+///
+/// Holds an array of `N` elements of seed material, each of `L` bytes, along
+/// with all other state in fixed-size buffers. No heap allocations are made
+/// when creating an instance or generating output, making it suitable for
+/// environments without an allocator. The algorithm is shared with [Drbg],
+/// and a `StaticDrbg` produces the same outputs as a [Drbg] created from the
+/// same array of seed material and context, for the same sequence of calls.
+/// It always uses the [CommitmentMode::Flat] commitment, as the Merkle
+/// commitment requires heap allocations.
+///
+/// # Generic Parameters
+/// - `D` - Hashing algorithm implementing the `Digest` trait.
+/// - `T` - Unsigned integer type for the counter, either `u32`, `u64`, or
+///   `u128`.
+/// - `N` - Number of elements in the array of seed material.
+/// - `L` - Length of each element in bytes. Must equal the digest output size
+///   of `D` when the seed material is initialized, as initialized elements
+///   are commitments of the digest length.
+/// - `S` - Stream backend used to generate output, [AesCtr] by default.
+///
+/// # Example
+/// ```
+/// use rand_core::RngCore;
+/// use sc_drbg::StaticDrbg;
+/// use sha3::Sha3_256;
+///
+/// let arr = [[0x42u8; 32]; 4];
+///
+/// let mut drbg =
+///     StaticDrbg::<Sha3_256, u64, 4, 32>::new_le(&arr, Some("firmware"), true)
+///         .expect("Should create new SC_DRBG instance");
+///
+/// let num = drbg.next_u64_subset(2);
+/// ```
+pub struct StaticDrbg<D, T, const N: usize, const L: usize, S = AesCtr>
+where
+    D: OutputSizeUser,
+{
+    arr: [[u8; L]; N],
+    prk: Output<D>,
+    keys: StateKeys<D>,
+    context: [u8; MAX_CONTEXT_LEN],
+    context_len: usize,
+    ctr: T,
+    endian: Endian,
+    _backend: PhantomData<S>,
+}
+
+const MAX_CONTEXT_LEN: usize = 128;
+
+impl<D, T, const N: usize, const L: usize, S> StaticDrbg<D, T, N, L, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Maximum length of the context string in bytes.
+    pub const MAX_CONTEXT_LEN: usize = MAX_CONTEXT_LEN;
+    /// Create a new [StaticDrbg] instance, using little-endian byte order.
+    ///
+    /// Equivalent to [Drbg::new_le] for the same array of seed material.
+    ///
+    /// # Arguments
+    /// - `arr` - Array of seed material.
+    /// - `context` - Optional context string for domain separation.
+    /// - `init` - Boolean indicating if the seed material should be processed
+    ///   by an initialization function.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if the array of seed material is empty or has
+    /// empty elements, if the digest size of `D` is below 16 bytes, if the
+    /// context string exceeds [StaticDrbg::MAX_CONTEXT_LEN], or if `L` does
+    /// not equal the digest size of `D` when `init` is true.
+    pub fn new_le(
+        arr: &[[u8; L]; N],
+        context: Option<&str>,
+        init: bool,
+    ) -> Result<Self, DrbgError> {
+        Self::new(arr, context, Endian::LittleEndian, init)
+    }
+    /// Create a new [StaticDrbg] instance, using big-endian byte order.
+    ///
+    /// Equivalent to [Drbg::new_be] for the same array of seed material.
+    ///
+    /// # Arguments
+    /// - `arr` - Array of seed material.
+    /// - `context` - Optional context string for domain separation.
+    /// - `init` - Boolean indicating if the seed material should be processed
+    ///   by an initialization function.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if the array of seed material is empty or has
+    /// empty elements, if the digest size of `D` is below 16 bytes, if the
+    /// context string exceeds [StaticDrbg::MAX_CONTEXT_LEN], or if `L` does
+    /// not equal the digest size of `D` when `init` is true.
+    pub fn new_be(
+        arr: &[[u8; L]; N],
+        context: Option<&str>,
+        init: bool,
+    ) -> Result<Self, DrbgError> {
+        Self::new(arr, context, Endian::BigEndian, init)
+    }
+    /// Return the next random `u32`, seeded by a subset of elements from the
+    /// [StaticDrbg] state.
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [StaticDrbg::try_next_u32_subset] to handle counter exhaustion as an
+    /// error.
+    pub fn next_u32_subset(&mut self, subset: usize) -> u32 {
+        self.try_next_u32_subset(subset)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Return the next random `u64`, seeded by a subset of elements from the
+    /// [StaticDrbg] state.
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [StaticDrbg::try_next_u64_subset] to handle counter exhaustion as an
+    /// error.
+    pub fn next_u64_subset(&mut self, subset: usize) -> u64 {
+        self.try_next_u64_subset(subset)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Fills a destination buffer with random bytes, seeded by a subset of
+    /// elements from the [StaticDrbg] state.
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value. Use
+    /// [StaticDrbg::try_fill_bytes_subset] to handle counter exhaustion as an
+    /// error.
+    pub fn fill_bytes_subset(&mut self, subset: usize, dst: &mut [u8]) {
+        self.try_fill_bytes_subset(subset, dst)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Fallible variant of [StaticDrbg::next_u32_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. The state is left unchanged.
+    pub fn try_next_u32_subset(
+        &mut self,
+        subset: usize,
+    ) -> Result<u32, DrbgError> {
+        let mut bytes = [0u8; 4];
+        self.try_fill_bytes_subset(subset, &mut bytes)?;
+        match self.endian {
+            Endian::LittleEndian => Ok(u32::from_le_bytes(bytes)),
+            Endian::BigEndian => Ok(u32::from_be_bytes(bytes)),
+        }
+    }
+    /// Fallible variant of [StaticDrbg::next_u64_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. The state is left unchanged.
+    pub fn try_next_u64_subset(
+        &mut self,
+        subset: usize,
+    ) -> Result<u64, DrbgError> {
+        let mut bytes = [0u8; 8];
+        self.try_fill_bytes_subset(subset, &mut bytes)?;
+        match self.endian {
+            Endian::LittleEndian => Ok(u64::from_le_bytes(bytes)),
+            Endian::BigEndian => Ok(u64::from_be_bytes(bytes)),
+        }
+    }
+    /// Fallible variant of [StaticDrbg::fill_bytes_subset].
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. The state and destination buffer are left unchanged.
+    pub fn try_fill_bytes_subset(
+        &mut self,
+        subset: usize,
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.fill_bytes_with_input(subset, &[], dst)
+    }
+    /// Fills a destination buffer with random bytes, seeded by a subset of
+    /// elements from the [StaticDrbg] state and bound to additional input.
+    ///
+    /// Equivalent to [Drbg::fill_bytes_with_input].
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    /// - `additional_input` - Additional input bound to the output.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. The state and destination buffer are left unchanged.
+    pub fn fill_bytes_with_input(
+        &mut self,
+        subset: usize,
+        additional_input: &[u8],
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        // Clamp subset to array length
+        let subset = subset.min(N);
+        // Check to prevent counter overflow
+        self.check_counter()?;
+        // Finalize subset of elements using PRK and counter
+        let mut scratch = [T::from(0); N];
+        let encode = Self::encoder(self.endian);
+        let decode = Self::decoder(self.endian);
+        Prf::<D>::next::<T, S, _>(
+            &self.arr,
+            self.context(),
+            &self.keys,
+            &mut scratch,
+            subset,
+            self.ctr,
+            additional_input,
+            encode,
+            decode,
+            dst,
+        );
+        // Update the state from the output and additional input
+        self.advance(dst, additional_input);
+        Ok(())
+    }
+    /// Fills a destination buffer with random bytes, seeded by an explicit
+    /// set of elements from the [StaticDrbg] state.
+    ///
+    /// Equivalent to [Drbg::fill_bytes_with_elements].
+    ///
+    /// # Arguments
+    /// - `indices` - Indices of the elements from the array of seed material
+    ///   to seed the generator with.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::EmptySelection] if no indices are given,
+    /// [DrbgError::IndexOutOfRange] or [DrbgError::DuplicateIndex] if the
+    /// indices are invalid, or [DrbgError::CounterExhausted] if the counter
+    /// has reached its maximum value. The state and destination buffer are
+    /// left unchanged on error.
+    pub fn fill_bytes_with_elements(
+        &mut self,
+        indices: &[usize],
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        if indices.is_empty() {
+            return Err(DrbgError::EmptySelection);
+        }
+        // Check indices without allocating, only building the error on failure
+        let mut seen = [false; N];
+        for &i in indices {
+            if i >= N || seen[i] {
+                return Drbg::<D, T, S>::validate_indices(indices, N);
+            }
+            seen[i] = true;
+        }
+        // Check to prevent counter overflow
+        self.check_counter()?;
+        // Finalize the given elements using PRK and counter
+        let mut k_i = [T::from(0); N];
+        for (k, &i) in k_i.iter_mut().zip(indices) {
+            *k = T::from_usize(i);
+        }
+        let encode = Self::encoder(self.endian);
+        Prf::<D>::next_with_indices::<T, S, _>(
+            &self.arr,
+            self.context(),
+            &self.keys,
+            &k_i[..indices.len()],
+            self.ctr,
+            &[],
+            encode,
+            dst,
+        );
+        // Update the state from the output
+        self.advance(dst, &[]);
+        Ok(())
+    }
+    /// Reseed the [StaticDrbg] state with additional seed material.
+    ///
+    /// Equivalent to [Drbg::reseed].
+    ///
+    /// # Arguments
+    /// - `additional` - Array of additional seed material.
+    /// - `label` - Optional label for domain separation between reseeds.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if the array of additional seed material is
+    /// empty or has empty elements.
+    pub fn reseed(
+        &mut self,
+        additional: &[&[u8]],
+        label: Option<&str>,
+    ) -> Result<(), DrbgError> {
+        Drbg::<D, T, S>::validate_array(additional)?;
+        // PRK to re-mix elements, from the current PRK, label, and all
+        // additional elements, salted with the context
+        let encode = Self::encoder(self.endian);
+        let mut tmp_prk = Prf::<D>::reseed(
+            self.context(),
+            &self.prk,
+            additional,
+            label.unwrap_or(""),
+            encode,
+        );
+        // Mix the array and derive the PRK for the next PRF call
+        self.evolve(&tmp_prk);
+        tmp_prk.as_mut_slice().zeroize();
+        // Reset counter
+        self.ctr = T::from(0);
+        Ok(())
+    }
+    fn new(
+        arr: &[[u8; L]; N],
+        context: Option<&str>,
+        endian: Endian,
+        init: bool,
+    ) -> Result<Self, DrbgError> {
+        Drbg::<D, T, S>::validate_array(arr)?;
+        Drbg::<D, T, S>::validate_digest()?;
+        let context = context.unwrap_or("");
+        if context.len() > MAX_CONTEXT_LEN {
+            return Err(DrbgError::ContextTooLong {
+                len: context.len(),
+                max: MAX_CONTEXT_LEN,
+            });
+        }
+        let digest_len = <D as OutputSizeUser>::output_size();
+        if init && L != digest_len {
+            return Err(DrbgError::InvalidElementLength {
+                expected: digest_len,
+                found: L,
+            });
+        }
+        let encode = Self::encoder(endian);
+        let mut state = *arr;
+        if init {
+            // Nonce from a hash of the array of seed material, and one round
+            // of mixing, as with the constructors of Drbg
+            let mut hasher = D::new();
+            for element in arr {
+                hasher.update(element);
+            }
+            let mut nonce = hasher.finalize();
+            Prf::<D>::initialize(arr, context, &nonce, 1, encode, &mut state);
+            nonce.as_mut_slice().zeroize();
+        }
+        // PRK from HKDF-Extract over all elements, salted with the context
+        let prk = Prf::<D>::extract(
+            &[context.as_bytes(), b"-OUTPUT"],
+            state.iter().map(|e| &e[..]),
+        );
+        // Keys for the initial state
+        let keys =
+            Prf::<D>::keys(&state, context, &prk, CommitmentMode::Flat, encode);
+        let mut context_buf = [0u8; MAX_CONTEXT_LEN];
+        context_buf[..context.len()].copy_from_slice(context.as_bytes());
+        let drbg = Self {
+            arr: state,
+            prk,
+            keys,
+            context: context_buf,
+            context_len: context.len(),
+            ctr: T::from(0),
+            endian,
+            _backend: PhantomData,
+        };
+        state.zeroize();
+        Ok(drbg)
+    }
+    fn context(&self) -> &str {
+        core::str::from_utf8(&self.context[..self.context_len])
+            .expect("context should be valid UTF-8")
+    }
+    fn encoder(endian: Endian) -> fn(T) -> T::Bytes {
+        match endian {
+            Endian::LittleEndian => T::to_le_array,
+            Endian::BigEndian => T::to_be_array,
+        }
+    }
+    fn decoder(endian: Endian) -> fn(&[u8]) -> T {
+        match endian {
+            Endian::LittleEndian => T::from_le_bytes,
+            Endian::BigEndian => T::from_be_bytes,
+        }
+    }
+    fn check_counter(&self) -> Result<(), DrbgError> {
+        if self.ctr == T::MAX {
+            return Err(DrbgError::CounterExhausted {
+                max: T::MAX.as_u128(),
+            });
+        }
+        Ok(())
+    }
+    fn advance(&mut self, dst: &[u8], input: &[u8]) {
+        // Increment counter
+        self.ctr = self.ctr.wrapping_add(T::from(1));
+        // PRK to re-mix elements, salted with the context
+        let encode = Self::encoder(self.endian);
+        let mut tmp_prk = Prf::<D>::update(self.context(), dst, input, encode);
+        // Mix the array and derive the PRK for the next PRF call
+        self.evolve(&tmp_prk);
+        tmp_prk.as_mut_slice().zeroize();
+    }
+    fn evolve(&mut self, tmp_prk: &[u8]) {
+        // Mix the array in place, and derive the PRK for the updated state
+        let encode = Self::encoder(self.endian);
+        let context = core::str::from_utf8(&self.context[..self.context_len])
+            .expect("context should be valid UTF-8");
+        let mut next_prk =
+            Prf::<D>::evolve(&mut self.arr, context, tmp_prk, encode);
+        self.prk.copy_from_slice(&next_prk);
+        next_prk.as_mut_slice().zeroize();
+        // Commitment and PRF keys, reused until the next state transition
+        self.keys = Prf::<D>::keys(
+            &self.arr,
+            context,
+            &self.prk,
+            CommitmentMode::Flat,
+            encode,
+        );
+    }
+}
+
+impl<D, T, const N: usize, const L: usize, S> RngCore
+    for StaticDrbg<D, T, N, L, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Return the next random `u32`, seeded by all elements.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value.
+    fn next_u32(&mut self) -> u32 {
+        self.next_u32_subset(N)
+    }
+    /// Return the next random `u64`, seeded by all elements.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value.
+    fn next_u64(&mut self) -> u64 {
+        self.next_u64_subset(N)
+    }
+    /// Fills a destination buffer with random bytes, seeded by all elements.
+    ///
+    /// # Panics
+    /// This method will panic if the counter reaches its maximum value.
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.fill_bytes_subset(N, dst);
+    }
+}
+
+impl<D, T, const N: usize, const L: usize, S> CryptoRng
+    for StaticDrbg<D, T, N, L, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+}
+
+impl<D, T, const N: usize, const L: usize, S> Drop for StaticDrbg<D, T, N, L, S>
+where
+    D: OutputSizeUser,
+{
+    fn drop(&mut self) {
+        self.prk.as_mut_slice().zeroize();
+        for element in &mut self.arr {
+            element.zeroize();
+        }
+    }
+}
+
+impl<D, T, const N: usize, const L: usize, S> ZeroizeOnDrop
+    for StaticDrbg<D, T, N, L, S>
+where
+    D: OutputSizeUser,
+{
+}
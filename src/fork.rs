@@ -0,0 +1,92 @@
+use crate::{
+    Drbg, Endian, backend::StreamBackend, prf::Prf, traits::UnsignedInt,
+};
+use alloc::{string::ToString, vec::Vec};
+use digest::{
+    Digest, HashMarker, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+use zeroize::{Zeroize, Zeroizing};
+
+impl<D, T, S> Drbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Fork an independent child generator from the [Drbg] state.
+    ///
+    /// A child key and a parent key are derived from the current PRK, salted
+    /// with the context and label. The child array of seed material is a copy
+    /// of the parent array with one round of mixing keyed by the child key,
+    /// and the child PRK is derived from the mixed array. The parent array is
+    /// then mixed with the parent key, advancing the parent state as after an
+    /// output, so that later outputs of the parent are independent of the
+    /// child. As with [Drbg::ratchet], the parent counter is reset to zero,
+    /// starting a new sequence of outputs for [Drbg::output_at].
+    ///
+    /// The child uses the context string, byte order, commitment mode,
+    /// evolution mode, and stream backend of the parent, and its counter
+    /// starts at zero. Forking is deterministic: the same parent state and
+    /// label always produce the same child. Forking again with the same label
+    /// produces a different child, as the parent state advances after each
+    /// fork.
+    ///
+    /// # Arguments
+    /// - `label` - Label for domain separation between children.
+    pub fn fork(&mut self, label: &str) -> Drbg<D, T, S> {
+        // Keys to mix the child and parent arrays, from the current PRK
+        let (mut child_key, mut parent_key) =
+            Prf::<D>::fork(&self.context, &self.prk, label);
+        // Mix a copy of the array for the child
+        let mut arr = Zeroizing::new(self.arr.clone());
+        match self.endian {
+            Endian::LittleEndian => {
+                Prf::<D>::mix(&mut arr, &child_key, 1, T::to_le_array)
+            }
+            Endian::BigEndian => {
+                Prf::<D>::mix(&mut arr, &child_key, 1, T::to_be_array)
+            }
+        }
+        let child = Self::new_from(
+            &arr,
+            Some(&self.context),
+            self.endian,
+            self.commitment,
+            self.evolution,
+        );
+        // Mix the parent array and derive the PRK for the next PRF call
+        self.evolve(&parent_key);
+        // Reset counter
+        self.ctr = T::from(0);
+        child_key.as_mut_slice().zeroize();
+        parent_key.as_mut_slice().zeroize();
+        child
+    }
+    /// Split the [Drbg] into `n` independent child generators, such as for a
+    /// pool of workers.
+    ///
+    /// Equivalent to calling [Drbg::fork] `n` times with the labels `0` to
+    /// `n - 1` in order, so the first children of a split do not depend on
+    /// `n`. The parent state advances once per child.
+    ///
+    /// # Arguments
+    /// - `n` - Number of child generators.
+    pub fn split_n(&mut self, n: usize) -> Vec<Drbg<D, T, S>> {
+        (0..n).map(|i| self.fork(&i.to_string())).collect()
+    }
+}
@@ -6,14 +6,45 @@
 //! each output to be generated from a configurable subset of array elements.
 //!
 //! # Features
-//! - Support for 32 and 64 bit unsigned integers.
+//! - Support for 32, 64, and 128 bit unsigned integers.
 //! - Configurable endianness.
-//! - Can specify the number of elements (1 to N) used to produce each output.
-//! - Commitment of array elements to their positions, lengths, and contents.
+//! - AES-CTR, ChaCha20, or cSHAKE256 output, selected at the type level.
+//! - Can specify the number of elements (1 to N) used to produce each output,
+//!   or an explicit set of elements.
+//! - Commitment of array elements to their positions, lengths, and contents,
+//!   optionally as a Merkle tree with element inclusion proofs.
 //! - Configurable rounds of mixing for entropy diffusion across elements.
+//! - Builder for configuring the initialization nonce, rounds, and byte order.
 //! - Provides forward security through state evolution.
-//! - Implements `RngCore` for compatibility with the Rust random ecosystem.
-//! - Secure memory zeroization on drop.
+//! - Streaming large outputs via `io::Read`, with one state update per
+//!   segment.
+//! - Reseeding with additional seed material.
+//! - Forking independent child generators, and splitting a generator for
+//!   pools of workers.
+//! - Seekable mode with random access to outputs by counter value, and
+//!   explicit ratcheting for forward secrecy.
+//! - `QuorumDrbg` for shared custody, generating output from an incomplete
+//!   set of elements verified against a published Merkle commitment.
+//! - Per-call additional input, as in NIST SP 800-90A, for domain separation
+//!   of outputs per request.
+//! - State snapshots for persisting and resuming a generator.
+//! - `AuditedDrbg` recording a hash-chained transcript of calls, which can be
+//!   verified by replaying it from the original seed material.
+//! - Implements `RngCore`, `CryptoRng`, and `SeedableRng` for compatibility
+//!   with the Rust random ecosystem, and `SeededDrbg` with the element layout
+//!   of `SeedableRng` seeds set by a const generic.
+//! - `DynDrbg` with the hashing algorithm, counter width, and byte order
+//!   selected at runtime from a descriptor such as `SHA3-256/u64/BE`, behind
+//!   the `dyn` feature.
+//! - Heap-free `StaticDrbg` variant with a fixed-capacity array, using const
+//!   generics.
+//! - Secure memory zeroization on drop, including intermediate key material.
+//! - `Debug` output and accessors exposing the configuration of a generator,
+//!   with the array of seed material and PRK redacted.
+//! - `no_std` support with `alloc`, by disabling the default `std` feature.
+//! - `sc-drbg` command-line tool behind the `cli` feature.
+//! - Known-answer test vector format, generator, and checker behind the
+//!   `vectors` feature.
 //!
 //! # Example
 //! ```
@@ -40,32 +71,72 @@
 //! }
 //! ```
 
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod audit;
+mod backend;
+mod builder;
+#[cfg(feature = "dyn")]
+mod dynamic;
 mod errors;
+mod fixed;
+mod fork;
+mod merkle;
 mod prf;
+mod quorum;
+mod seeded;
+mod seek;
+mod state;
+mod stream;
 mod traits;
+#[cfg(feature = "vectors")]
+mod vectors;
 
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+pub use audit::{AuditedDrbg, TranscriptEntry, verify_transcript};
+pub use backend::{AesCtr, ChaCha20, Shake256Xof, StreamBackend};
+pub use builder::DrbgBuilder;
+use core::{any::type_name, fmt, marker::PhantomData};
 use digest::{
     Digest, HashMarker, OutputSizeUser,
     block_buffer::Eager,
     core_api::{
-        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+        AlgorithmName, BlockSizeUser, BufferKindUser, CoreProxy,
+        FixedOutputCore, UpdateCore,
     },
     typenum::{IsLess, Le, NonZero, U256},
 };
+#[cfg(feature = "dyn")]
+pub use dynamic::{Algorithm, CounterWidth, DigestAlgorithm, DynDrbg};
 pub use errors::DrbgError;
-use hkdf::Hkdf;
-use prf::Prf;
-use rand_core::RngCore;
-use std::marker::PhantomData;
+pub use fixed::StaticDrbg;
+pub use merkle::{CommitmentMode, ElementProof, verify_element_proof};
+use prf::{Prf, StateKeys};
+pub use quorum::QuorumDrbg;
+use rand_core::{CryptoRng, RngCore, SeedableRng, TryCryptoRng, TryRngCore};
+pub use seeded::SeededDrbg;
+pub use seek::EvolutionMode;
+pub use stream::DrbgStream;
 pub use traits::UnsignedInt;
-use zeroize::Zeroize;
+#[cfg(feature = "vectors")]
+pub use vectors::{Call, TestVector, VectorFile};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// Byte order for integer encoding and decoding.
 ///
-/// Specifies how 32 and 64 bit integers are converted to and from bytes.
-/// during SC_DRBG operations. This choice affects deterministic output and
-/// should match the endianness of other operations.
-#[derive(Copy, Clone)]
+/// Specifies how 32, 64, and 128 bit integers are converted to and from
+/// bytes during SC_DRBG operations. This choice affects deterministic output
+/// and should match the endianness of other operations.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Endian {
     /// Little-endian byte order.
     LittleEndian,
@@ -78,30 +149,48 @@ pub enum Endian {
 ///
 /// `Drbg` generates pseudorandom bytes from an initial array of seed
 /// material. The generator maintains an internal state that evolves after
-/// each output, providing forward secrecy.
+/// each output, providing forward secrecy. With [EvolutionMode::Seekable], the
+/// state instead only evolves on [Drbg::ratchet], allowing random access to
+/// outputs with [Drbg::output_at].
+///
+/// The `Debug` output shows the configuration of the generator, including the
+/// hashing algorithm, counter width, element lengths, context string, and
+/// counter value, while redacting the array of seed material and PRK.
 ///
 /// # Generic Parameters
 /// - `D` - A hashing algorithm implementing the `Digest` trait (e.g.,
-/// `Sha256`, `Sha512`).
+///   `Sha256`, `Sha512`).
 /// - `T` - Integer type for the counter and other integer values used
-/// internally. Must be `u32` or `u64`.
+///   internally. Must be `u32`, `u64`, or `u128`.
+/// - `S` - Stream backend implementing the [StreamBackend] trait, used to
+///   generate output from the derived PRF key and nonce. One of [AesCtr]
+///   (the default), [ChaCha20], or [Shake256Xof].
 ///
 /// # Security Considerations
 /// The generator's security depends on the seed array containing sufficient
 /// entropy. Low entropy inputs should be properly handled before use with
-/// `Drbg`. The counter will panic if it reaches its maximum value (`u32::MAX`
-/// or `u64::MAX`). Lastly, all outputs are deterministic given the same array
-/// of seed material, context, and operations.
-pub struct Drbg<D, T> {
+/// `Drbg`. The counter will panic if it reaches its maximum value (`u32::MAX`,
+/// `u64::MAX`, or `u128::MAX`), unless one of the fallible `try_` methods is
+/// used, which return [DrbgError::CounterExhausted] instead. Lastly, all
+/// outputs are deterministic given the same array of seed material, context,
+/// and operations.
+pub struct Drbg<D, T, S = AesCtr>
+where
+    D: OutputSizeUser,
+{
     arr: Vec<Vec<u8>>,
     prk: Vec<u8>,
+    keys: StateKeys<D>,
     context: String,
     ctr: T,
     endian: Endian,
+    commitment: CommitmentMode,
+    evolution: EvolutionMode,
     _digest: PhantomData<D>,
+    _backend: PhantomData<S>,
 }
 
-impl<D, T> Drbg<D, T>
+impl<D, T, S> Drbg<D, T, S>
 where
     D: Digest + CoreProxy + OutputSizeUser,
     D::Core: Sync
@@ -115,6 +204,7 @@ where
     <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
     Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
     T: UnsignedInt,
+    S: StreamBackend,
 {
     /// Create a new [Drbg] instance, using little-endian byte order, from an
     /// array of seed material and a context string.
@@ -124,23 +214,16 @@ where
     /// of the array, this creates commitments and applies one round of mixing
     /// before creating the new instance from the processed material.
     pub fn new_le(
-        arr: &Vec<Vec<u8>>,
+        arr: &[Vec<u8>],
         context: Option<&str>,
         init: bool,
     ) -> Result<Self, DrbgError> {
-        Self::validate_array(arr)?;
-        Self::validate_digest()?;
-        let endian = Endian::LittleEndian;
-        if init {
-            let arr_concat: Vec<u8> = arr.iter().flatten().copied().collect();
-            let mut hasher = D::new();
-            hasher.update(&arr_concat);
-            let nonce = hasher.finalize().to_vec();
-            let arr_init = Self::initialize(&arr, context, nonce, 1, endian);
-            Ok(Self::new_from(&arr_init, context, endian))
-        } else {
-            Ok(Self::new_from(&arr, context, endian))
-        }
+        Self::builder()
+            .array(arr)
+            .context(context)
+            .endian(Endian::LittleEndian)
+            .init(init)
+            .build()
     }
     /// Create a new [Drbg] instance, using big-endian byte order, from an
     /// array of seed material and a context string.
@@ -150,23 +233,28 @@ where
     /// of the array, this creates commitments and applies one round of mixing
     /// before creating the new instance from the processed material.
     pub fn new_be(
-        arr: &Vec<Vec<u8>>,
+        arr: &[Vec<u8>],
         context: Option<&str>,
         init: bool,
     ) -> Result<Self, DrbgError> {
-        Self::validate_array(arr)?;
-        Self::validate_digest()?;
-        let endian = Endian::BigEndian;
-        if init {
-            let arr_concat: Vec<u8> = arr.iter().flatten().copied().collect();
-            let mut hasher = D::new();
-            hasher.update(&arr_concat);
-            let nonce = hasher.finalize().to_vec();
-            let arr_init = Self::initialize(&arr, context, nonce, 1, endian);
-            Ok(Self::new_from(&arr_init, context, endian))
-        } else {
-            Ok(Self::new_from(&arr, context, endian))
-        }
+        Self::builder()
+            .array(arr)
+            .context(context)
+            .endian(Endian::BigEndian)
+            .init(init)
+            .build()
+    }
+    /// Number of elements a seed is split into when creating a new [Drbg]
+    /// instance via `SeedableRng`. Use [SeededDrbg] for a different number
+    /// of elements.
+    pub const SEED_ELEMENTS: usize = 4;
+    /// Create a new [DrbgBuilder] for configuring a [Drbg] instance.
+    ///
+    /// The builder exposes the nonce, number of mixing rounds, and byte order
+    /// used during initialization, which the `new_le` and `new_be`
+    /// constructors otherwise set to their defaults.
+    pub fn builder() -> DrbgBuilder<D, T, S> {
+        DrbgBuilder::new()
     }
     /// Initialize an array of seed material.
     ///
@@ -190,52 +278,36 @@ where
     pub fn initialize(
         arr: &[Vec<u8>],
         context: Option<&str>,
-        nonce: Vec<u8>,
+        mut nonce: Vec<u8>,
         rounds: usize,
         endian: Endian,
     ) -> Vec<Vec<u8>> {
-        // Key length based on hashing algorithm
+        // Initialized elements are commitments of the digest length
         let key_len = <D as OutputSizeUser>::output_size();
-        // Concatenate all array elements
-        let arr_concat: Vec<u8> = arr.iter().flatten().copied().collect();
-        // PRK from HKDF-Extract, expand into commit and mix keys
-        let prk = Self::derive_prk(&arr_concat, &nonce);
-        let hk = Hkdf::<D>::from_prk(&prk).expect("PRK should be large enough");
-        // Commitments key
-        let mut key_1 = vec![0u8; key_len];
-        let mut info = format!("{}-COMMIT", context.unwrap_or(""));
-        hk.expand(&info.as_bytes().to_vec(), &mut key_1)
-            .expect("okm length should match the hash digest length");
-        // Mixing key
-        let mut key_2 = vec![0u8; key_len];
-        info = format!("{}-MIX", context.unwrap_or(""));
-        hk.expand(&info.as_bytes().to_vec(), &mut key_2)
-            .expect("okm length should match the hash digest length");
-        // Commit each element to their position, length, and content
-        let committed: Vec<Vec<u8>>;
-        match endian {
-            Endian::LittleEndian => {
-                committed =
-                    Prf::<D>::init_commits(&arr, &key_1, T::to_le_bytes);
-            }
-            Endian::BigEndian => {
-                committed =
-                    Prf::<D>::init_commits(&arr, &key_1, T::to_be_bytes);
-            }
-        }
-        // Mix with rounds of SHAKE256 for entropy diffusion across elements
-        let mixed: Vec<Vec<u8>>;
+        let mut arr_init = vec![vec![0u8; key_len]; arr.len()];
+        // Commit each element to their position, length, and content, then
+        // mix with rounds of SHAKE256 for entropy diffusion across elements
+        let context = context.unwrap_or("");
         match endian {
-            Endian::LittleEndian => {
-                mixed =
-                    Prf::<D>::mix(&committed, &key_2, rounds, T::to_le_bytes);
-            }
-            Endian::BigEndian => {
-                mixed =
-                    Prf::<D>::mix(&committed, &key_2, rounds, T::to_be_bytes);
-            }
+            Endian::LittleEndian => Prf::<D>::initialize(
+                arr,
+                context,
+                &nonce,
+                rounds,
+                T::to_le_array,
+                &mut arr_init,
+            ),
+            Endian::BigEndian => Prf::<D>::initialize(
+                arr,
+                context,
+                &nonce,
+                rounds,
+                T::to_be_array,
+                &mut arr_init,
+            ),
         }
-        mixed
+        nonce.zeroize();
+        arr_init
     }
     /// Return the next random `u32`, seeded by a subset of elements from the
     /// [Drbg] state.
@@ -247,18 +319,16 @@ where
     ///
     /// # Arguments
     /// - `subset` - Number of elements from the array of seed material to seed
-    /// the generator with. Clamped to array length.
+    ///   the generator with. Clamped to array length.
     ///
     /// # Panics
     /// This method will panic if the counter reaches its maximum value
-    /// (`u32::MAX` or `u64::MAX`). This prevents counter overflow.
+    /// (`u32::MAX`, `u64::MAX`, or `u128::MAX`). This prevents counter
+    /// overflow. Use [Drbg::try_next_u32_subset] to handle counter exhaustion
+    /// as an error.
     pub fn next_u32_subset(&mut self, subset: usize) -> u32 {
-        let mut bytes = [0u8; 4];
-        self.fill_bytes_subset(subset, &mut bytes);
-        match self.endian {
-            Endian::LittleEndian => u32::from_le_bytes(bytes),
-            Endian::BigEndian => u32::from_be_bytes(bytes),
-        }
+        self.try_next_u32_subset(subset)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
     /// Return the next random `u64`, seeded by a subset of elements from the
     /// [Drbg] state.
@@ -270,18 +340,16 @@ where
     ///
     /// # Arguments
     /// - `subset` - Number of elements from the array of seed material to seed
-    /// the generator with. Clamped to array length.
+    ///   the generator with. Clamped to array length.
     ///
     /// # Panics
     /// This method will panic if the counter reaches its maximum value
-    /// (`u32::MAX` or `u64::MAX`). This prevents counter overflow.
+    /// (`u32::MAX`, `u64::MAX`, or `u128::MAX`). This prevents counter
+    /// overflow. Use [Drbg::try_next_u64_subset] to handle counter exhaustion
+    /// as an error.
     pub fn next_u64_subset(&mut self, subset: usize) -> u64 {
-        let mut bytes = [0u8; 8];
-        self.fill_bytes_subset(subset, &mut bytes);
-        match self.endian {
-            Endian::LittleEndian => u64::from_le_bytes(bytes),
-            Endian::BigEndian => u64::from_be_bytes(bytes),
-        }
+        self.try_next_u64_subset(subset)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
     /// Fills a destination buffer with random bytes, seeded by a subset of
     /// elements from the [Drbg] state.
@@ -294,136 +362,524 @@ where
     ///
     /// # Arguments
     /// - `subset` - Number of elements from the array of seed material to seed
-    /// the generator with. Clamped to array length.
+    ///   the generator with. Clamped to array length.
     /// - `dst` - Destination buffer to fill with random bytes.
     ///
     /// # Panics
     /// This method will panic if the counter reaches its maximum value
-    /// (`u32::MAX` or `u64::MAX`). This prevents counter overflow.
+    /// (`u32::MAX`, `u64::MAX`, or `u128::MAX`). This prevents counter
+    /// overflow. Use [Drbg::try_fill_bytes_subset] to handle counter exhaustion
+    /// as an error.
     pub fn fill_bytes_subset(&mut self, subset: usize, dst: &mut [u8]) {
+        self.try_fill_bytes_subset(subset, dst)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Return the next random `u32`, seeded by a subset of elements from the
+    /// [Drbg] state, or an error if the counter is exhausted.
+    ///
+    /// Fallible variant of [Drbg::next_u32_subset].
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. The state is left unchanged.
+    pub fn try_next_u32_subset(
+        &mut self,
+        subset: usize,
+    ) -> Result<u32, DrbgError> {
+        let mut bytes = [0u8; 4];
+        self.try_fill_bytes_subset(subset, &mut bytes)?;
+        match self.endian {
+            Endian::LittleEndian => Ok(u32::from_le_bytes(bytes)),
+            Endian::BigEndian => Ok(u32::from_be_bytes(bytes)),
+        }
+    }
+    /// Return the next random `u64`, seeded by a subset of elements from the
+    /// [Drbg] state, or an error if the counter is exhausted.
+    ///
+    /// Fallible variant of [Drbg::next_u64_subset].
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. The state is left unchanged.
+    pub fn try_next_u64_subset(
+        &mut self,
+        subset: usize,
+    ) -> Result<u64, DrbgError> {
+        let mut bytes = [0u8; 8];
+        self.try_fill_bytes_subset(subset, &mut bytes)?;
+        match self.endian {
+            Endian::LittleEndian => Ok(u64::from_le_bytes(bytes)),
+            Endian::BigEndian => Ok(u64::from_be_bytes(bytes)),
+        }
+    }
+    /// Fills a destination buffer with random bytes, seeded by a subset of
+    /// elements from the [Drbg] state, or returns an error if the counter is
+    /// exhausted.
+    ///
+    /// Fallible variant of [Drbg::fill_bytes_subset].
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. The state and destination buffer are left unchanged.
+    pub fn try_fill_bytes_subset(
+        &mut self,
+        subset: usize,
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.fill_bytes_selected(subset, &[], dst).map(|_| ())
+    }
+    /// Fills a destination buffer with random bytes, seeded by a subset of
+    /// elements from the [Drbg] state and bound to additional input.
+    ///
+    /// Mixes per-request additional input into the output, in the manner of
+    /// the additional input of NIST SP 800-90A generators. The input is bound
+    /// into the derivation of the PRF key and nonce, and into the state
+    /// update, so that callers can domain-separate outputs per request, such
+    /// as per user ID, without creating new generators. Calls with empty
+    /// input produce the same output and state as [Drbg::fill_bytes_subset].
+    ///
+    /// With [EvolutionMode::Seekable], there is no state update between
+    /// outputs, so the input is bound into the output alone. The state is
+    /// left as after [Drbg::fill_bytes_subset], and later outputs, as well
+    /// as [Drbg::ratchet], do not depend on the input. [Drbg::output_at]
+    /// reproduces outputs generated without additional input only.
+    ///
+    /// # Arguments
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    /// - `additional_input` - Additional input bound to the output.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter has reached its
+    /// maximum value. The state and destination buffer are left unchanged.
+    pub fn fill_bytes_with_input(
+        &mut self,
+        subset: usize,
+        additional_input: &[u8],
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        self.fill_bytes_selected(subset, additional_input, dst)
+            .map(|_| ())
+    }
+    pub(crate) fn fill_bytes_selected(
+        &mut self,
+        subset: usize,
+        input: &[u8],
+        dst: &mut [u8],
+    ) -> Result<Vec<T>, DrbgError> {
         // Clamp subset to array length
         let subset = subset.min(self.arr.len());
         // Check to prevent counter overflow
-        match T::SIZE {
-            4 => {
-                if self.ctr == T::MAX {
-                    panic!("Counter exhausted u32 range")
-                }
-            }
-            8 => {
-                if self.ctr == T::MAX {
-                    panic!("Counter exhausted u64 range")
-                }
-            }
-            _ => unreachable!("Only u32 and u64 supported"),
-        }
+        self.check_counter()?;
         // Finalize subset of elements using PRK and counter
-        match &mut self.endian {
-            Endian::LittleEndian => Prf::<D>::next(
+        let indices = self.generate(subset, self.ctr, input, dst);
+        // Update the state from the output and additional input
+        self.advance(dst, input);
+        Ok(indices)
+    }
+    fn generate(
+        &self,
+        subset: usize,
+        ctr: T,
+        input: &[u8],
+        dst: &mut [u8],
+    ) -> Vec<T> {
+        self.generate_with(&self.keys, subset, ctr, input, dst)
+    }
+    fn generate_with(
+        &self,
+        keys: &StateKeys<D>,
+        subset: usize,
+        ctr: T,
+        input: &[u8],
+        dst: &mut [u8],
+    ) -> Vec<T> {
+        // Finalize subset of elements using PRK and counter, with scratch
+        // space for selecting indices
+        let mut scratch = vec![T::from(0); self.arr.len()];
+        match self.endian {
+            Endian::LittleEndian => Prf::<D>::next::<T, S, _>(
                 &self.arr,
                 &self.context,
-                &self.prk,
+                keys,
+                &mut scratch,
                 subset,
-                self.ctr,
-                T::to_le_bytes,
+                ctr,
+                input,
+                T::to_le_array,
                 T::from_le_bytes,
                 dst,
             ),
-            Endian::BigEndian => Prf::<D>::next(
+            Endian::BigEndian => Prf::<D>::next::<T, S, _>(
                 &self.arr,
                 &self.context,
-                &self.prk,
+                keys,
+                &mut scratch,
                 subset,
-                self.ctr,
-                T::to_be_bytes,
+                ctr,
+                input,
+                T::to_be_array,
                 T::from_be_bytes,
                 dst,
             ),
         }
-        // Increment counter
-        self.ctr = self.ctr.wrapping_add(T::from(1));
-        // Prepend the context to the label
-        let label = format!("{}-UPDATE", &self.context);
-        let label_bytes = &label.as_bytes().to_vec();
-        // PRK to re-mix elements
-        let mut tmp_prk = Self::derive_prk(&dst.to_vec(), &label_bytes);
-        // Mix the array from the current state
-        let tmp_arr = match self.endian {
-            Endian::LittleEndian => {
-                Prf::<D>::mix(&self.arr, &tmp_prk, 1, T::to_le_bytes)
-            }
-            Endian::BigEndian => {
-                Prf::<D>::mix(&self.arr, &tmp_prk, 1, T::to_be_bytes)
-            }
+        // Return the selected indices, at the start of the scratch space
+        scratch.truncate(subset);
+        scratch
+    }
+    /// Fills a destination buffer with random bytes, seeded by an explicit
+    /// set of elements from the [Drbg] state.
+    ///
+    /// Generates output by finalizing the elements at the given indices in
+    /// the array of seed material with the current counter value, instead of
+    /// a subset selected by the PRF. The output depends only on which
+    /// elements are selected, not on the order of the indices, and matches
+    /// the output of [Drbg::fill_bytes_subset] whenever the PRF would select
+    /// the same elements. Provides forward secrecy by updating the internal
+    /// state after each call, in the same way as [Drbg::fill_bytes_subset].
+    ///
+    /// # Arguments
+    /// - `indices` - Indices of the elements from the array of seed material
+    ///   to seed the generator with.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::EmptySelection] if no indices are given,
+    /// [DrbgError::IndexOutOfRange] or [DrbgError::DuplicateIndex] if the
+    /// indices are invalid, or [DrbgError::CounterExhausted] if the counter
+    /// has reached its maximum value. The state and destination buffer are
+    /// left unchanged on error.
+    pub fn fill_bytes_with_elements(
+        &mut self,
+        indices: &[usize],
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        // Output seeded by no elements would use no secret material
+        if indices.is_empty() {
+            return Err(DrbgError::EmptySelection);
+        }
+        Self::validate_indices(indices, self.arr.len())?;
+        // Check to prevent counter overflow
+        self.check_counter()?;
+        // Finalize the given elements using PRK and counter
+        let k_i: Vec<T> = indices.iter().map(|&i| T::from_usize(i)).collect();
+        match self.endian {
+            Endian::LittleEndian => Prf::<D>::next_with_indices::<T, S, _>(
+                &self.arr,
+                &self.context,
+                &self.keys,
+                &k_i,
+                self.ctr,
+                &[],
+                T::to_le_array,
+                dst,
+            ),
+            Endian::BigEndian => Prf::<D>::next_with_indices::<T, S, _>(
+                &self.arr,
+                &self.context,
+                &self.keys,
+                &k_i,
+                self.ctr,
+                &[],
+                T::to_be_array,
+                dst,
+            ),
+        }
+        // Update the state from the output
+        self.advance(dst, &[]);
+        Ok(())
+    }
+    /// Return the current value of the counter.
+    ///
+    /// The counter is incremented after every output, and reset to zero by
+    /// [Drbg::reseed].
+    pub fn counter(&self) -> T {
+        self.ctr
+    }
+    /// Return the number of elements in the array of seed material.
+    pub fn element_count(&self) -> usize {
+        self.arr.len()
+    }
+    /// Return the context string used for domain separation.
+    ///
+    /// Returns an empty string if the [Drbg] was created without a context
+    /// string.
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+    /// Return the byte order used for integer encoding and decoding.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+    /// Return an adapter implementing `TryRngCore` for the [Drbg].
+    ///
+    /// `Drbg` implements `RngCore`, so it also implements `TryRngCore` through
+    /// the blanket implementation in `rand_core`, with an infallible error
+    /// type. The returned [TryDrbg] instead reports counter exhaustion as a
+    /// [DrbgError], for use with APIs accepting a fallible generator.
+    pub fn try_rng(&mut self) -> TryDrbg<'_, D, T, S> {
+        TryDrbg { drbg: self }
+    }
+    /// Return a [DrbgStream] adapter for generating large outputs.
+    ///
+    /// The stream generates output in segments of `segment_len` bytes, each
+    /// from a single state, seeded by all elements. The forward secrecy update
+    /// is applied once per segment, so reading a large output costs one state
+    /// update per segment instead of one per read. The output is identical to
+    /// repeated [Drbg::fill_bytes_subset] calls using all elements with a
+    /// buffer of `segment_len` bytes.
+    ///
+    /// # Arguments
+    /// - `segment_len` - Number of bytes generated from each state. Clamped
+    ///   to a minimum of 1.
+    pub fn stream(&mut self, segment_len: usize) -> DrbgStream<'_, D, T, S> {
+        DrbgStream::new(self, segment_len)
+    }
+    /// Reseed the [Drbg] state with additional seed material.
+    ///
+    /// Injects fresh entropy into the live array of seed material. A mixing
+    /// key is derived from the current PRK, the label, and the additional
+    /// material, each prefixed by its length so that distinct arrays and
+    /// labels never share an encoding. One round of mixing is applied so
+    /// that every element absorbs the new material. A new PRK is then derived
+    /// from the mixed state, and the counter is reset to zero.
+    ///
+    /// Reseeding allows a long-running generator to recover from a suspected
+    /// state compromise, provided the additional material contains sufficient
+    /// entropy that is unknown to an attacker.
+    ///
+    /// # Arguments
+    /// - `additional` - Array of additional seed material.
+    /// - `label` - Optional label for domain separation between reseeds.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if the array of additional seed material is
+    /// empty or has empty elements.
+    pub fn reseed(
+        &mut self,
+        additional: &[Vec<u8>],
+        label: Option<&str>,
+    ) -> Result<(), DrbgError> {
+        Self::validate_array(additional)?;
+        // PRK to re-mix elements, from the current PRK, label, and all
+        // additional elements, salted with the context
+        let label = label.unwrap_or("");
+        let mut tmp_prk = match self.endian {
+            Endian::LittleEndian => Prf::<D>::reseed(
+                &self.context,
+                &self.prk,
+                additional,
+                label,
+                T::to_le_array,
+            ),
+            Endian::BigEndian => Prf::<D>::reseed(
+                &self.context,
+                &self.prk,
+                additional,
+                label,
+                T::to_be_array,
+            ),
         };
-        // Concatenate all array elements
-        let arr_concat: Vec<u8> = tmp_arr.iter().flatten().copied().collect();
-        // Prepend the context to the label
-        let label = format!("{}-NEXT", &self.context);
-        let label_bytes = &label.as_bytes().to_vec();
-        // PRK for the updated state, used in the next PRF call
-        tmp_prk = Self::derive_prk(&arr_concat, &label_bytes);
-        // Update instance with mixed array and new PRK
-        self.arr = tmp_arr;
-        self.prk = tmp_prk;
-    }
-    fn validate_array(arr: &Vec<Vec<u8>>) -> Result<(), DrbgError> {
+        // Mix the array and derive the PRK for the next PRF call
+        self.evolve(&tmp_prk);
+        tmp_prk.as_mut_slice().zeroize();
+        // Reset counter
+        self.ctr = T::from(0);
+        Ok(())
+    }
+    pub(crate) fn validate_array<E>(arr: &[E]) -> Result<(), DrbgError>
+    where
+        E: AsRef<[u8]>,
+    {
         if arr.is_empty() {
             return Err(DrbgError::EmptyArray);
         }
         let empty_elements: Vec<usize> = arr
             .iter()
             .enumerate()
-            .filter_map(
-                |(i, element)| if element.is_empty() { Some(i) } else { None },
-            )
+            .filter_map(|(i, element)| {
+                if element.as_ref().is_empty() {
+                    Some(i)
+                } else {
+                    None
+                }
+            })
             .collect();
         if !empty_elements.is_empty() {
             return Err(DrbgError::EmptyElement(empty_elements));
         }
         Ok(())
     }
-    fn validate_digest() -> Result<(), DrbgError> {
+    pub(crate) fn validate_digest() -> Result<(), DrbgError> {
         let digest_len = <D as OutputSizeUser>::output_size();
         if digest_len < 16 {
             return Err(DrbgError::DigestTooSmall(digest_len));
         }
         Ok(())
     }
-    fn derive_prk(ikm: &Vec<u8>, salt: &Vec<u8>) -> Vec<u8> {
-        // PRK length based on hashing algorithm
-        let prk_len = <D as OutputSizeUser>::output_size();
-        // PRK from HKDF-Extract
-        let (prk_arr, _) = Hkdf::<D>::extract(Some(&salt), &ikm);
-        let mut prk = vec![0u8; prk_len];
-        prk.copy_from_slice(&prk_arr);
-        prk
-    }
-    fn new_from(
-        arr: &Vec<Vec<u8>>,
+    pub(crate) fn validate_indices(
+        indices: &[usize],
+        len: usize,
+    ) -> Result<(), DrbgError> {
+        let out_of_range: Vec<usize> =
+            indices.iter().copied().filter(|&i| i >= len).collect();
+        if !out_of_range.is_empty() {
+            return Err(DrbgError::IndexOutOfRange(out_of_range));
+        }
+        let mut seen = vec![false; len];
+        let mut duplicates: Vec<usize> = Vec::new();
+        for &i in indices {
+            if seen[i] && !duplicates.contains(&i) {
+                duplicates.push(i);
+            }
+            seen[i] = true;
+        }
+        if !duplicates.is_empty() {
+            return Err(DrbgError::DuplicateIndex(duplicates));
+        }
+        Ok(())
+    }
+    fn check_counter(&self) -> Result<(), DrbgError> {
+        if self.ctr == T::MAX {
+            return Err(DrbgError::CounterExhausted {
+                max: T::MAX.as_u128(),
+            });
+        }
+        Ok(())
+    }
+    fn advance(&mut self, dst: &[u8], input: &[u8]) {
+        // Increment counter
+        self.ctr = self.ctr.wrapping_add(T::from(1));
+        // A seekable state only evolves when ratcheted, so additional input
+        // is bound into the output alone
+        if self.evolution == EvolutionMode::Seekable {
+            return;
+        }
+        // PRK to re-mix elements, salted with the context
+        let mut tmp_prk = match self.endian {
+            Endian::LittleEndian => {
+                Prf::<D>::update(&self.context, dst, input, T::to_le_array)
+            }
+            Endian::BigEndian => {
+                Prf::<D>::update(&self.context, dst, input, T::to_be_array)
+            }
+        };
+        // Mix the array and derive the PRK for the next PRF call
+        self.evolve(&tmp_prk);
+        tmp_prk.as_mut_slice().zeroize();
+    }
+    fn evolve(&mut self, tmp_prk: &[u8]) {
+        // Mix the array in place, and derive the PRK for the updated state
+        let mut next_prk = match self.endian {
+            Endian::LittleEndian => Prf::<D>::evolve(
+                &mut self.arr,
+                &self.context,
+                tmp_prk,
+                T::to_le_array,
+            ),
+            Endian::BigEndian => Prf::<D>::evolve(
+                &mut self.arr,
+                &self.context,
+                tmp_prk,
+                T::to_be_array,
+            ),
+        };
+        // Update instance with new PRK, and keys for the new state
+        self.prk.copy_from_slice(&next_prk);
+        next_prk.as_mut_slice().zeroize();
+        self.keys = Self::state_keys(
+            &self.arr,
+            &self.context,
+            &self.prk,
+            self.endian,
+            self.commitment,
+        );
+    }
+    #[cfg(feature = "bench")]
+    pub(crate) fn uncached_keys(&self) -> StateKeys<D> {
+        // Keys for the current state, recomputed with every subkey
+        match self.endian {
+            Endian::LittleEndian => Prf::<D>::uncached_keys(
+                &self.arr,
+                &self.context,
+                &self.prk,
+                self.commitment,
+                T::to_le_array,
+            ),
+            Endian::BigEndian => Prf::<D>::uncached_keys(
+                &self.arr,
+                &self.context,
+                &self.prk,
+                self.commitment,
+                T::to_be_array,
+            ),
+        }
+    }
+    pub(crate) fn state_keys(
+        arr: &[Vec<u8>],
+        context: &str,
+        prk: &[u8],
+        endian: Endian,
+        commitment: CommitmentMode,
+    ) -> StateKeys<D> {
+        // Commitment and PRF keys, reused until the next state transition
+        match endian {
+            Endian::LittleEndian => {
+                Prf::<D>::keys(arr, context, prk, commitment, T::to_le_array)
+            }
+            Endian::BigEndian => {
+                Prf::<D>::keys(arr, context, prk, commitment, T::to_be_array)
+            }
+        }
+    }
+    pub(crate) fn new_from(
+        arr: &[Vec<u8>],
         context: Option<&str>,
         endian: Endian,
+        commitment: CommitmentMode,
+        evolution: EvolutionMode,
     ) -> Self {
-        // Concatenate all array elements
-        let arr_concat: Vec<u8> = arr.iter().flatten().copied().collect();
-        // Prepend the context to the label
-        let label = format!("{}-OUTPUT", context.unwrap_or(""));
-        let label_bytes = &label.as_bytes().to_vec();
-        // PRK from HKDF-Extract
-        let prk = Self::derive_prk(&arr_concat, &label_bytes);
+        // PRK from HKDF-Extract over all elements, salted with the context
+        let context = context.unwrap_or("");
+        let mut prk = Prf::<D>::extract(
+            &[context.as_bytes(), b"-OUTPUT"],
+            arr.iter().map(|e| &e[..]),
+        );
+        // Keys for the initial state
+        let keys = Self::state_keys(arr, context, &prk, endian, commitment);
         // Return Drbg instance
-        Self {
+        let drbg = Self {
             arr: arr.to_vec(),
-            prk: prk,
-            context: context.unwrap_or("").to_string(),
+            prk: prk.to_vec(),
+            keys,
+            context: context.to_string(),
             ctr: T::from(0),
-            endian: endian,
+            endian,
+            commitment,
+            evolution,
             _digest: PhantomData,
-        }
+            _backend: PhantomData,
+        };
+        prk.as_mut_slice().zeroize();
+        drbg
     }
 }
 
-impl<D, T> RngCore for Drbg<D, T>
+impl<D, T, S> RngCore for Drbg<D, T, S>
 where
     D: Digest + CoreProxy + OutputSizeUser,
     D::Core: Sync
@@ -437,6 +893,7 @@ where
     <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
     Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
     T: UnsignedInt,
+    S: StreamBackend,
 {
     /// Return the next random `u32`.
     ///
@@ -447,9 +904,10 @@ where
     ///
     /// # Panics
     /// This method will panic if the counter reaches its maximum value
-    /// (`u32::MAX` or `u64::MAX`). This prevents counter overflow.
+    /// (`u32::MAX`, `u64::MAX`, or `u128::MAX`). This prevents counter
+    /// overflow. Use [Drbg::try_rng] to handle counter exhaustion as an error.
     fn next_u32(&mut self) -> u32 {
-        return self.next_u32_subset(self.arr.len());
+        self.next_u32_subset(self.arr.len())
     }
     /// Return the next random `u64`.
     ///
@@ -460,9 +918,10 @@ where
     ///
     /// # Panics
     /// This method will panic if the counter reaches its maximum value
-    /// (`u32::MAX` or `u64::MAX`). This prevents counter overflow.
+    /// (`u32::MAX`, `u64::MAX`, or `u128::MAX`). This prevents counter
+    /// overflow. Use [Drbg::try_rng] to handle counter exhaustion as an error.
     fn next_u64(&mut self) -> u64 {
-        return self.next_u64_subset(self.arr.len());
+        self.next_u64_subset(self.arr.len())
     }
     /// Fills a destination buffer with random bytes.
     ///
@@ -477,17 +936,213 @@ where
     ///
     /// # Panics
     /// This method will panic if the counter reaches its maximum value
-    /// (`u32::MAX` or `u64::MAX`). This prevents counter overflow.
+    /// (`u32::MAX`, `u64::MAX`, or `u128::MAX`). This prevents counter
+    /// overflow. Use [Drbg::try_rng] to handle counter exhaustion as an error.
     fn fill_bytes(&mut self, dst: &mut [u8]) {
         self.fill_bytes_subset(self.arr.len(), dst);
     }
 }
 
-impl<D, T> Drop for Drbg<D, T> {
+impl<D, T, S> CryptoRng for Drbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+}
+
+impl<D, T, S> SeedableRng for Drbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    type Seed = [u8; 32];
+
+    /// Create a new [Drbg] instance from a 32 byte seed.
+    ///
+    /// The seed is split into [Drbg::SEED_ELEMENTS] elements of equal length,
+    /// forming the array of seed material. The new instance uses little-endian
+    /// byte order and no context string, and the seed material is initialized
+    /// in the same way as [Drbg::new_le]. The same seed always produces the
+    /// same generator, as with a [SeededDrbg] of [Drbg::SEED_ELEMENTS]
+    /// elements. Use [SeededDrbg] or [DrbgBuilder::seed] for a different
+    /// element layout.
+    ///
+    /// # Panics
+    /// This method will panic if the digest size of `D` is below 16 bytes.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::builder()
+            .seed(&seed, Self::SEED_ELEMENTS)
+            .build()
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl<D, T, S> Drop for Drbg<D, T, S>
+where
+    D: OutputSizeUser,
+{
     fn drop(&mut self) {
+        // The PRF keys are zeroized by the drop of StateKeys
         self.prk.zeroize();
         for element in &mut self.arr {
             element.zeroize();
         }
     }
 }
+
+impl<D, T, S> ZeroizeOnDrop for Drbg<D, T, S> where D: OutputSizeUser {}
+
+impl<D, T, S> fmt::Debug for Drbg<D, T, S>
+where
+    D: CoreProxy + OutputSizeUser,
+    D::Core: AlgorithmName,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Only the lengths of elements are shown, and the array of seed
+        // material and PRK are redacted
+        let element_lens: Vec<usize> = self.arr.iter().map(Vec::len).collect();
+        f.debug_struct("Drbg")
+            .field("digest", &DigestName::<D>(PhantomData))
+            .field("counter_width", &format_args!("{}", type_name::<T>()))
+            .field("backend", &format_args!("{}", S::NAME))
+            .field("endian", &self.endian)
+            .field("commitment", &self.commitment)
+            .field("evolution", &self.evolution)
+            .field("element_count", &self.arr.len())
+            .field("element_lens", &element_lens)
+            .field("context", &self.context)
+            .field("counter", &self.ctr.as_u128())
+            .field("arr", &format_args!("<redacted>"))
+            .field("prk", &format_args!("<redacted>"))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Name of the hashing algorithm `D`, for the `Debug` output of [Drbg].
+///
+/// Core types name SHA-2 digests by their state size and output length, such
+/// as `Sha256_32`, so SHA-2 and SHA-3 names are mapped to their standard
+/// form, such as `SHA-256` or `SHA3-256`. Other names are shown as they are.
+struct DigestName<D>(PhantomData<D>);
+
+/// Name of the hashing algorithm `D`, as written by its core type.
+struct CoreName<D>(PhantomData<D>);
+
+impl<D> fmt::Debug for DigestName<D>
+where
+    D: CoreProxy + OutputSizeUser,
+    D::Core: AlgorithmName,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("{}", CoreName::<D>(PhantomData));
+        let bits = <D as OutputSizeUser>::output_size() * 8;
+        if name.starts_with("Sha3_") {
+            write!(f, "SHA3-{}", bits)
+        } else if name.starts_with("Sha256")
+            || (name.starts_with("Sha512") && bits >= 384)
+        {
+            write!(f, "SHA-{}", bits)
+        } else if name.starts_with("Sha512") {
+            write!(f, "SHA-512/{}", bits)
+        } else {
+            f.write_str(&name)
+        }
+    }
+}
+
+impl<D> fmt::Display for CoreName<D>
+where
+    D: CoreProxy,
+    D::Core: AlgorithmName,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <D::Core as AlgorithmName>::write_alg_name(f)
+    }
+}
+
+/// Adapter implementing `TryRngCore` for a [Drbg], returned by
+/// [Drbg::try_rng].
+///
+/// Each method seeds the generator with all elements from the array of seed
+/// material, and returns [DrbgError::CounterExhausted] instead of panicking
+/// when the counter reaches its maximum value.
+pub struct TryDrbg<'a, D, T, S = AesCtr>
+where
+    D: OutputSizeUser,
+{
+    drbg: &'a mut Drbg<D, T, S>,
+}
+
+impl<D, T, S> TryRngCore for TryDrbg<'_, D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    type Error = DrbgError;
+
+    fn try_next_u32(&mut self) -> Result<u32, DrbgError> {
+        let subset = self.drbg.arr.len();
+        self.drbg.try_next_u32_subset(subset)
+    }
+    fn try_next_u64(&mut self) -> Result<u64, DrbgError> {
+        let subset = self.drbg.arr.len();
+        self.drbg.try_next_u64_subset(subset)
+    }
+    fn try_fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), DrbgError> {
+        let subset = self.drbg.arr.len();
+        self.drbg.try_fill_bytes_subset(subset, dst)
+    }
+}
+
+impl<D, T, S> TryCryptoRng for TryDrbg<'_, D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+}
@@ -0,0 +1,176 @@
+use crate::{
+    Drbg, DrbgError, Endian, backend::StreamBackend, prf::Prf,
+    traits::UnsignedInt,
+};
+use alloc::vec::Vec;
+use digest::{
+    Digest, HashMarker, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+
+/// Commitment scheme binding the array of seed material of a [Drbg] state.
+///
+/// The commitment binds every element to its position, length, and content,
+/// and keys the selection of elements and the derivation of output. It is
+/// recomputed after each state transition.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum CommitmentMode {
+    /// Single hash over the element count and every element, in order.
+    #[default]
+    Flat,
+    /// Root of a Merkle tree with one leaf per element, bound to the element
+    /// count. Allows proving that an element is part of the state with
+    /// [Drbg::prove_element], without disclosing the other elements.
+    ///
+    /// Each leaf is blinded with a secret derived from the state, which is
+    /// only revealed in the proof for that leaf. The sibling hashes in a
+    /// proof therefore cannot be used to test guesses of the other elements,
+    /// even when those elements have little entropy.
+    Merkle,
+}
+
+/// Inclusion proof for one element of a [Drbg] state, created by
+/// [Drbg::prove_element] and checked with [verify_element_proof].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ElementProof {
+    /// Index of the element in the array of seed material.
+    pub index: usize,
+    /// Number of elements in the array of seed material.
+    pub count: usize,
+    /// Byte order used to encode integers in the tree.
+    pub endian: Endian,
+    /// Secret blinding of the leaf, revealed for this element only.
+    pub blinding: Vec<u8>,
+    /// Sibling nodes on the path from the leaf to the top of the tree.
+    pub siblings: Vec<Vec<u8>>,
+}
+
+impl<D, T, S> Drbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Return the [CommitmentMode] of the [Drbg].
+    pub fn commitment_mode(&self) -> CommitmentMode {
+        self.commitment
+    }
+    /// Return the commitment to the current array of seed material.
+    ///
+    /// With [CommitmentMode::Merkle], this is the Merkle root that element
+    /// proofs from [Drbg::prove_element] are verified against. The commitment
+    /// changes after every state transition, so it should be published
+    /// before the first output to commit to the seed material itself. Use
+    /// `init(false)` on the [DrbgBuilder](crate::DrbgBuilder) so that the
+    /// state holds the published elements, rather than their initialized
+    /// form.
+    pub fn commitment_root(&self) -> Vec<u8> {
+        self.keys.commit().to_vec()
+    }
+    /// Create an inclusion proof for an element of the current array of seed
+    /// material.
+    ///
+    /// The proof holds only blinded hashes of the other elements, along with
+    /// the blinding of this element, and can be checked against
+    /// [Drbg::commitment_root] with [verify_element_proof], given the element
+    /// itself.
+    ///
+    /// # Arguments
+    /// - `index` - Index of the element in the array of seed material.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::ProofUnavailable] if the [Drbg] does not use
+    /// [CommitmentMode::Merkle], or [DrbgError::IndexOutOfRange] if the index
+    /// is outside the bounds of the array.
+    pub fn prove_element(
+        &self,
+        index: usize,
+    ) -> Result<ElementProof, DrbgError> {
+        if self.commitment != CommitmentMode::Merkle {
+            return Err(DrbgError::ProofUnavailable);
+        }
+        Self::validate_indices(&[index], self.arr.len())?;
+        let (path, blinding) = match self.endian {
+            Endian::LittleEndian => Prf::<D>::merkle_path(
+                &self.arr,
+                &self.context,
+                &self.prk,
+                index,
+                T::to_le_array,
+            ),
+            Endian::BigEndian => Prf::<D>::merkle_path(
+                &self.arr,
+                &self.context,
+                &self.prk,
+                index,
+                T::to_be_array,
+            ),
+        };
+        Ok(ElementProof {
+            index,
+            count: self.arr.len(),
+            endian: self.endian,
+            blinding: blinding.to_vec(),
+            siblings: path.iter().map(|node| node.to_vec()).collect(),
+        })
+    }
+}
+
+/// Verify an inclusion proof created by [Drbg::prove_element].
+///
+/// Returns `true` if `element` is at the index recorded in the proof, within
+/// an array of seed material with the Merkle commitment `root`. The hashing
+/// algorithm `D` and counter type `T` must match those of the [Drbg] that
+/// created the proof.
+///
+/// # Arguments
+/// - `root` - Commitment returned by [Drbg::commitment_root].
+/// - `element` - Element of seed material to check.
+/// - `proof` - Inclusion proof for the element.
+pub fn verify_element_proof<D, T>(
+    root: &[u8],
+    element: &[u8],
+    proof: &ElementProof,
+) -> bool
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+{
+    let encode = match proof.endian {
+        Endian::LittleEndian => T::to_le_array,
+        Endian::BigEndian => T::to_be_array,
+    };
+    Prf::<D>::merkle_verify::<T>(
+        root,
+        proof.index,
+        proof.count,
+        element,
+        &proof.blinding,
+        &proof.siblings,
+        encode,
+    )
+}
@@ -1,21 +1,22 @@
-use crate::traits::UnsignedInt;
-use aes::{
-    Aes128, Aes192, Aes256,
-    cipher::{KeyIvInit, StreamCipher},
+use crate::{
+    backend::StreamBackend, merkle::CommitmentMode, traits::UnsignedInt,
 };
-use ctr::Ctr128BE;
+use alloc::vec::Vec;
+use core::{iter, marker::PhantomData};
 use digest::{
-    Digest, ExtendableOutput, HashMarker, OutputSizeUser, Update, XofReader,
+    Digest, ExtendableOutput, HashMarker, Output, OutputSizeUser, Update,
+    XofReader,
     block_buffer::Eager,
     core_api::{
-        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+        Block, BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore,
+        UpdateCore,
     },
     typenum::{IsLess, Le, NonZero, U256},
 };
 use hkdf::{self, Hkdf};
 use hmac::{Hmac, Mac};
 use sha3::Shake256;
-use std::marker::PhantomData;
+use zeroize::Zeroize;
 
 const D_1: u8 = 0x01;
 const D_2: u8 = 0x02;
@@ -24,6 +25,51 @@ const D_4: u8 = 0x04;
 const D_5: u8 = 0x05;
 const D_6: u8 = 0x06;
 const D_7: u8 = 0x07;
+const D_8: u8 = 0x08;
+const D_9: u8 = 0x09;
+const D_10: u8 = 0x0A;
+const D_11: u8 = 0x0B;
+const D_12: u8 = 0x0C;
+
+/// Maximum PRF key length in bytes supported by a stream backend.
+pub const MAX_KEY_LEN: usize = 64;
+/// Maximum nonce length in bytes supported by a stream backend.
+pub const MAX_NONCE_LEN: usize = 16;
+
+/// Keys derived from a [Drbg](crate::Drbg) state, computed once per state
+/// transition and reused by the PRF. In the sequential evolution mode every
+/// output is a state transition, so they are reused across outputs only in
+/// the seekable mode.
+pub struct StateKeys<D>
+where
+    D: OutputSizeUser,
+{
+    commit: Output<D>,
+    subkeys: Output<D>,
+    indices: Output<D>,
+    prf: Output<D>,
+}
+
+impl<D> StateKeys<D>
+where
+    D: OutputSizeUser,
+{
+    pub fn commit(&self) -> &[u8] {
+        &self.commit
+    }
+}
+
+impl<D> Drop for StateKeys<D>
+where
+    D: OutputSizeUser,
+{
+    fn drop(&mut self) {
+        self.commit.as_mut_slice().zeroize();
+        self.subkeys.as_mut_slice().zeroize();
+        self.indices.as_mut_slice().zeroize();
+        self.prf.as_mut_slice().zeroize();
+    }
+}
 
 pub struct Prf<D> {
     _digest: PhantomData<D>,
@@ -43,222 +89,674 @@ where
     <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
     Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
 {
-    pub fn init_commits<T>(
-        arr: &[Vec<u8>],
-        key: &[u8],
-        encode: fn(T) -> Vec<u8>,
-    ) -> Vec<Vec<u8>>
+    pub fn extract<'a, I>(salt: &[&[u8]], ikm: I) -> Output<D>
     where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        // HKDF-Extract is HMAC keyed by the salt, over the input key material
+        let mut mac = Self::salted_mac(salt);
+        for part in ikm {
+            Mac::update(&mut mac, part);
+        }
+        Self::mac_output(mac)
+    }
+    fn mac_output(mac: Hmac<D>) -> Output<D> {
+        // Copy the MAC digest into a digest length output
+        let mut bytes = mac.finalize().into_bytes();
+        let mut out = Output::<D>::default();
+        out.copy_from_slice(&bytes);
+        bytes.as_mut_slice().zeroize();
+        out
+    }
+    fn salted_mac(salt: &[&[u8]]) -> Hmac<D> {
+        // Assemble the salt in a block sized buffer. Salts longer than a block
+        // are hashed first, as HMAC does with long keys
+        let salt_len: usize = salt.iter().map(|part| part.len()).sum();
+        let mut block = Block::<D::Core>::default();
+        let mac = if salt_len > block.len() {
+            let mut hasher = D::new();
+            for part in salt {
+                hasher.update(part);
+            }
+            let mut digest = hasher.finalize();
+            let mac = Hmac::<D>::new_from_slice(&digest)
+                .expect("HMAC can take key of any size");
+            digest.as_mut_slice().zeroize();
+            mac
+        } else {
+            let mut pos = 0;
+            for part in salt {
+                block[pos..pos + part.len()].copy_from_slice(part);
+                pos += part.len();
+            }
+            Hmac::<D>::new_from_slice(&block[..salt_len])
+                .expect("HMAC can take key of any size")
+        };
+        block.as_mut_slice().zeroize();
+        mac
+    }
+    fn expand(prk: &[u8], info: &[&[u8]]) -> Output<D> {
+        // Expand PRK into a key of the digest length
+        let hk = Hkdf::<D>::from_prk(prk).expect("PRK should be large enough");
+        let mut key = Output::<D>::default();
+        hk.expand_multi_info(info, &mut key)
+            .expect("okm length should match the hash digest length");
+        key
+    }
+    pub fn initialize<T, E, O>(
+        arr: &[E],
+        context: &str,
+        nonce: &[u8],
+        rounds: usize,
+        encode: fn(T) -> T::Bytes,
+        out: &mut [O],
+    ) where
+        T: UnsignedInt,
+        E: AsRef<[u8]>,
+        O: AsRef<[u8]> + AsMut<[u8]>,
+    {
+        // PRK from HKDF-Extract over all elements, expand into commit and mix
+        // keys
+        let mut prk = Self::extract(&[nonce], arr.iter().map(AsRef::as_ref));
+        let mut key_1 = Self::expand(&prk, &[context.as_bytes(), b"-COMMIT"]);
+        let mut key_2 = Self::expand(&prk, &[context.as_bytes(), b"-MIX"]);
+        // Commit each element to their position, length, and content
+        Self::init_commits::<T, E, O>(arr, &key_1, encode, out);
+        // Mix with rounds of SHAKE256 for entropy diffusion across elements
+        Self::mix::<T, O>(out, &key_2, rounds, encode);
+        prk.as_mut_slice().zeroize();
+        key_1.as_mut_slice().zeroize();
+        key_2.as_mut_slice().zeroize();
+    }
+    fn init_commits<T, E, O>(
+        arr: &[E],
+        key: &[u8],
+        encode: fn(T) -> T::Bytes,
+        out: &mut [O],
+    ) where
         T: UnsignedInt,
+        E: AsRef<[u8]>,
+        O: AsMut<[u8]>,
     {
-        // Initialize element commitments
-        let mut commits = Vec::with_capacity(arr.len());
         // Commit each element to its position, length, and content
-        for i in 0..arr.len() {
+        for (i, (element, commit)) in arr.iter().zip(out.iter_mut()).enumerate()
+        {
+            let element = element.as_ref();
             // Initialize MAC using key
-            let mut mac = Hmac::<D>::new_from_slice(&key)
+            let mut mac = Hmac::<D>::new_from_slice(key)
                 .expect("HMAC can take key of any size");
             // Absorb domain byte
             Mac::update(&mut mac, &[D_1]);
             // Absorb element position, length, and contents
-            Mac::update(&mut mac, &encode(T::from_usize(i)));
-            Mac::update(&mut mac, &encode(T::from_usize(arr[i].len())));
-            Mac::update(&mut mac, &arr[i]);
-            // Add commitment to element commitments
-            commits.push(mac.finalize().into_bytes().to_vec());
+            Mac::update(&mut mac, encode(T::from_usize(i)).as_ref());
+            Mac::update(
+                &mut mac,
+                encode(T::from_usize(element.len())).as_ref(),
+            );
+            Mac::update(&mut mac, element);
+            // Write commitment in place of the element
+            let mut bytes = mac.finalize().into_bytes();
+            commit.as_mut().copy_from_slice(&bytes);
+            bytes.as_mut_slice().zeroize();
         }
-        commits
     }
-    pub fn mix<T>(
-        arr: &[Vec<u8>],
+    pub fn mix<T, E>(
+        arr: &mut [E],
         prk: &[u8],
         rounds: usize,
-        encode: fn(T) -> Vec<u8>,
-    ) -> Vec<Vec<u8>>
-    where
+        encode: fn(T) -> T::Bytes,
+    ) where
         T: UnsignedInt,
+        E: AsRef<[u8]> + AsMut<[u8]>,
     {
-        // Initialize mixed elements from array
-        let mut mixed = arr.to_vec();
-        let key_len = <D as OutputSizeUser>::output_size();
         // Apply rounds of mixing
         for i in 0..rounds {
             // Create info from round number, expand PRK into mixing key
-            let info = format!("ROUND{}", i);
-            let info_bytes = info.as_bytes();
-            let hk = hkdf::Hkdf::<D>::from_prk(prk)
-                .expect("PRK should be large enough");
-            let mut key = vec![0u8; key_len];
-            hk.expand(&info_bytes, &mut key)
-                .expect("okm length should match the hash digest length");
+            let mut digits = [0u8; 20];
+            let round = Self::decimal(i, &mut digits);
+            let mut key = Self::expand(prk, &[b"ROUND", round]);
             // Create tweak from mixing key and round
             let mut tweak_hasher = D::new();
             tweak_hasher.update(&key);
-            tweak_hasher.update(&encode(T::from_usize(i)));
-            let tweak = tweak_hasher.finalize().to_vec();
+            tweak_hasher.update(encode(T::from_usize(i)));
+            let mut tweak = tweak_hasher.finalize();
             // Create sponge, absorb tweak
             let mut sponge = Shake256::default();
             sponge.update(&tweak);
             // Absorb all elements and positions
-            for j in 0..mixed.len() {
-                sponge.update(&mixed[j]);
-                sponge.update(&encode(T::from_usize(j)));
+            for (j, element) in arr.iter().enumerate() {
+                sponge.update(element.as_ref());
+                sponge.update(encode(T::from_usize(j)).as_ref());
             }
-            // Squeeze outputs from sponge, use temporary buffer
+            // Squeeze outputs from sponge over the absorbed elements
             let mut sponge_reader = sponge.finalize_xof();
-            let mut tmp = Vec::with_capacity(mixed.len());
-            for j in 0..mixed.len() {
-                let mut element = vec![0u8; mixed[j].len()];
-                sponge_reader.read(&mut element);
-                tmp.push(element);
+            for element in arr.iter_mut() {
+                sponge_reader.read(element.as_mut());
+            }
+            key.as_mut_slice().zeroize();
+            tweak.as_mut_slice().zeroize();
+        }
+    }
+    pub fn evolve<T, E>(
+        arr: &mut [E],
+        context: &str,
+        tmp_prk: &[u8],
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
+    where
+        T: UnsignedInt,
+        E: AsRef<[u8]> + AsMut<[u8]>,
+    {
+        // Mix the array from the current state
+        Self::mix::<T, E>(arr, tmp_prk, 1, encode);
+        // PRK for the updated state, used in the next PRF call
+        Self::extract(
+            &[context.as_bytes(), b"-NEXT"],
+            arr.iter().map(AsRef::as_ref),
+        )
+    }
+    pub fn update<T>(
+        context: &str,
+        output: &[u8],
+        input: &[u8],
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
+    where
+        T: UnsignedInt,
+    {
+        // PRK to re-mix elements from the output, salted with the context
+        if input.is_empty() {
+            return Self::extract(&[context.as_bytes(), b"-UPDATE"], [output]);
+        }
+        // Bind additional input, with its length, into the update
+        let input_len = encode(T::from_usize(input.len()));
+        Self::extract(
+            &[context.as_bytes(), b"-UPDATE-INPUT"],
+            [output, input_len.as_ref(), input],
+        )
+    }
+    pub fn reseed<T, E>(
+        context: &str,
+        prk: &[u8],
+        additional: &[E],
+        label: &str,
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
+    where
+        T: UnsignedInt,
+        E: AsRef<[u8]>,
+    {
+        // PRK to re-mix elements from the current PRK, the label, and all
+        // additional elements, each with its length, salted with the context
+        let mut mac = Self::salted_mac(&[context.as_bytes(), b"-RESEED"]);
+        Mac::update(&mut mac, prk);
+        let parts = iter::once(label.as_bytes())
+            .chain(additional.iter().map(AsRef::as_ref));
+        for part in parts {
+            Mac::update(&mut mac, encode(T::from_usize(part.len())).as_ref());
+            Mac::update(&mut mac, part);
+        }
+        Self::mac_output(mac)
+    }
+    pub fn fork(
+        context: &str,
+        prk: &[u8],
+        label: &str,
+    ) -> (Output<D>, Output<D>) {
+        // PRK for the fork from the current PRK, salted with the context and
+        // label
+        let mut fork_prk = Self::extract(
+            &[context.as_bytes(), b"-FORK-", label.as_bytes()],
+            [prk],
+        );
+        // Expand into keys to mix the child and parent arrays
+        let context = context.as_bytes();
+        let child = Self::expand(&fork_prk, &[context, b"-FORK-CHILD"]);
+        let parent = Self::expand(&fork_prk, &[context, b"-FORK-PARENT"]);
+        fork_prk.as_mut_slice().zeroize();
+        (child, parent)
+    }
+    pub fn keys<T, E>(
+        arr: &[E],
+        context: &str,
+        prk: &[u8],
+        mode: CommitmentMode,
+        encode: fn(T) -> T::Bytes,
+    ) -> StateKeys<D>
+    where
+        T: UnsignedInt,
+        E: AsRef<[u8]>,
+    {
+        // Create commitment, expand PRK into keys for each step
+        let commit = match mode {
+            CommitmentMode::Flat => Self::commitment::<T, E>(arr, encode),
+            CommitmentMode::Merkle => {
+                Self::merkle_root::<T, E>(arr, context, prk, encode)
             }
-            mixed = tmp;
+        };
+        Self::keys_with_commit(commit, context, prk)
+    }
+    #[cfg(feature = "bench")]
+    pub fn uncached_keys<T, E>(
+        arr: &[E],
+        context: &str,
+        prk: &[u8],
+        commitment: CommitmentMode,
+        encode: fn(T) -> T::Bytes,
+    ) -> StateKeys<D>
+    where
+        T: UnsignedInt,
+        E: AsRef<[u8]>,
+    {
+        // Keys for the state, with a subkey for every element, as derived
+        // for each output before they were cached
+        let keys = Self::keys::<T, E>(arr, context, prk, commitment, encode);
+        for (i, element) in arr.iter().enumerate() {
+            let mut subkey = Self::subkey::<T>(
+                &keys.subkeys,
+                i,
+                element.as_ref(),
+                &keys.commit,
+                encode,
+            );
+            core::hint::black_box(&subkey);
+            subkey.as_mut_slice().zeroize();
         }
-        mixed
+        keys
+    }
+    pub fn quorum(context: &str, root: &[u8]) -> Output<D> {
+        // PRK from the public commitment alone, salted with the context
+        Self::extract(&[context.as_bytes(), b"-QUORUM"], [root])
     }
-    pub fn next<T>(
-        arr: &[Vec<u8>],
+    pub fn keys_with_commit(
+        commit: Output<D>,
         context: &str,
         prk: &[u8],
+    ) -> StateKeys<D> {
+        // Expand PRK into keys for each step
+        let context = context.as_bytes();
+        StateKeys {
+            commit,
+            subkeys: Self::expand(prk, &[context, b"-SUBKEYS"]),
+            indices: Self::expand(prk, &[context, b"-INDICES"]),
+            prf: Self::expand(prk, &[context, b"-PRF"]),
+        }
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub fn next<T, S, E>(
+        arr: &[E],
+        context: &str,
+        keys: &StateKeys<D>,
+        scratch: &mut [T],
         subset: usize,
         counter: T,
-        encode: fn(T) -> Vec<u8>,
+        input: &[u8],
+        encode: fn(T) -> T::Bytes,
         decode: fn(&[u8]) -> T,
         dst: &mut [u8],
     ) where
         T: UnsignedInt,
+        S: StreamBackend,
+        E: AsRef<[u8]>,
     {
-        // Create commitment
-        let commit = Self::commitment::<T>(arr, encode);
-        // Expand PRK into keys for each step
-        let key_len = <D as OutputSizeUser>::output_size();
-        let hk = Hkdf::<D>::from_prk(prk).expect("PRK should be large enough");
-        let mut key_1 = vec![0u8; key_len];
-        let mut info = format!("{}-SUBKEYS", context);
-        hk.expand(&info.as_bytes(), &mut key_1)
-            .expect("okm length should match the hash digest length");
-        let mut key_2 = vec![0u8; key_len];
-        info = format!("{}-INDICES", context);
-        hk.expand(&info.as_bytes(), &mut key_2)
-            .expect("okm length should match the hash digest length");
-        let mut key_3 = vec![0u8; key_len];
-        info = format!("{}-PRF", context);
-        hk.expand(&info.as_bytes(), &mut key_3)
-            .expect("okm length should match the hash digest length");
-        // Create subkeys and select indices
-        let k_s = Self::subkeys::<T>(arr, &key_1, &commit, encode);
+        // Select indices, using scratch space of the array length
         let k_i = Self::indices::<T>(
-            &key_2,
-            &commit,
-            T::from_usize(arr.len()),
+            &keys.indices,
+            &keys.commit,
+            &mut scratch[..arr.len()],
             T::from_usize(subset),
             counter,
             encode,
             decode,
         );
-        // Bind each subkey to the commitment and counter, XOR into accumulator
-        let acc = Self::combine::<T>(&k_s, &k_i, &commit, counter, encode);
-        // Derive PRF key and nonce
-        let (prf_key, nonce) =
-            Self::derive_key_nonce::<T>(&key_3, &commit, counter, &acc, encode);
-        // Fill the destination buffer with zero bytes
-        dst.fill(0);
-        // Encrypt zero bytes using AES-CTR, change variant based on key size
-        match prf_key.len() {
-            16 => {
-                let mut aes_key = [0u8; 16];
-                aes_key.copy_from_slice(&prf_key);
-                let mut cipher =
-                    Ctr128BE::<Aes128>::new(&aes_key.into(), &nonce.into());
-                cipher.apply_keystream(dst);
-            }
-            24 => {
-                let mut aes_key = [0u8; 24];
-                aes_key.copy_from_slice(&prf_key);
-                let mut cipher =
-                    Ctr128BE::<Aes192>::new(&aes_key.into(), &nonce.into());
-                cipher.apply_keystream(dst);
-            }
-            32 => {
-                let mut aes_key = [0u8; 32];
-                aes_key.copy_from_slice(&prf_key);
-                let mut cipher =
-                    Ctr128BE::<Aes256>::new(&aes_key.into(), &nonce.into());
-                cipher.apply_keystream(dst);
-            }
-            _ => panic!("key length {} is invalid for AES-CTR", prf_key.len()),
-        }
+        // Finalize the selected elements
+        Self::finalize::<T, S, E>(
+            arr, context, keys, k_i, counter, input, encode, dst,
+        );
     }
-    fn commitment<T>(arr: &[Vec<u8>], encode: fn(T) -> Vec<u8>) -> Vec<u8>
+    #[allow(clippy::too_many_arguments)]
+    pub fn next_with_indices<T, S, E>(
+        arr: &[E],
+        context: &str,
+        keys: &StateKeys<D>,
+        indices: &[T],
+        counter: T,
+        input: &[u8],
+        encode: fn(T) -> T::Bytes,
+        dst: &mut [u8],
+    ) where
+        T: UnsignedInt,
+        S: StreamBackend,
+        E: AsRef<[u8]>,
+    {
+        // Finalize the given elements
+        Self::finalize::<T, S, E>(
+            arr, context, keys, indices, counter, input, encode, dst,
+        );
+    }
+    #[allow(clippy::too_many_arguments)]
+    fn finalize<T, S, E>(
+        arr: &[E],
+        context: &str,
+        keys: &StateKeys<D>,
+        indices: &[T],
+        counter: T,
+        input: &[u8],
+        encode: fn(T) -> T::Bytes,
+        dst: &mut [u8],
+    ) where
+        T: UnsignedInt,
+        S: StreamBackend,
+        E: AsRef<[u8]>,
+    {
+        // Derive subkeys for the selected elements, bind each to the
+        // commitment and counter, XOR into accumulator
+        let mut acc = Self::combine::<T, E>(
+            arr,
+            &keys.subkeys,
+            indices,
+            &keys.commit,
+            counter,
+            encode,
+        );
+        // Derive PRF key and nonce into stack buffers
+        let key_len = S::key_len(<D as OutputSizeUser>::output_size());
+        assert!(
+            key_len <= MAX_KEY_LEN && S::NONCE_LEN <= MAX_NONCE_LEN,
+            "{} key or nonce length exceeds the supported maximum",
+            S::NAME
+        );
+        let mut prf_key = [0u8; MAX_KEY_LEN];
+        let mut nonce = [0u8; MAX_NONCE_LEN];
+        Self::derive_key_nonce::<T, S>(
+            &keys.prf,
+            &keys.commit,
+            counter,
+            &acc,
+            input,
+            encode,
+            &mut prf_key[..key_len],
+            &mut nonce[..S::NONCE_LEN],
+        );
+        // Fill the destination buffer using the output backend
+        S::fill(
+            &prf_key[..key_len],
+            &nonce[..S::NONCE_LEN],
+            &acc,
+            context,
+            dst,
+        );
+        acc.as_mut_slice().zeroize();
+        prf_key.zeroize();
+        nonce.zeroize();
+    }
+    fn commitment<T, E>(arr: &[E], encode: fn(T) -> T::Bytes) -> Output<D>
     where
         T: UnsignedInt,
+        E: AsRef<[u8]>,
     {
         // Initialize hasher
         let mut hasher = D::new();
         // Absorb domain byte
-        hasher.update(&[D_2]);
+        hasher.update([D_2]);
         // Encode and absorb element count
-        hasher.update(&encode(T::from_usize(arr.len())));
+        hasher.update(encode(T::from_usize(arr.len())));
         // For each element, commit to its position, length, and content
-        for i in 0..arr.len() {
+        for (i, element) in arr.iter().enumerate() {
+            let element = element.as_ref();
             // Absorb element position, length, and contents
-            hasher.update(&encode(T::from_usize(i)));
-            hasher.update(&encode(T::from_usize(arr[i].len())));
-            hasher.update(&arr[i]);
+            hasher.update(encode(T::from_usize(i)));
+            hasher.update(encode(T::from_usize(element.len())));
+            hasher.update(element);
         }
         // Return commitment
-        hasher.finalize().to_vec()
+        hasher.finalize()
+    }
+    pub fn merkle_root<T, E>(
+        arr: &[E],
+        context: &str,
+        prk: &[u8],
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
+    where
+        T: UnsignedInt,
+        E: AsRef<[u8]>,
+    {
+        // Hash each blinded element into a leaf, then combine levels up to
+        // the top
+        let mut level = Self::merkle_leaves::<T, E>(arr, context, prk, encode);
+        while level.len() > 1 {
+            level = Self::merkle_level(level);
+        }
+        // Bind the top node to the element count
+        let root = Self::merkle_commit::<T>(arr.len(), &level[0], encode);
+        level[0].as_mut_slice().zeroize();
+        root
+    }
+    pub fn merkle_path<T, E>(
+        arr: &[E],
+        context: &str,
+        prk: &[u8],
+        index: usize,
+        encode: fn(T) -> T::Bytes,
+    ) -> (Vec<Output<D>>, Output<D>)
+    where
+        T: UnsignedInt,
+        E: AsRef<[u8]>,
+    {
+        // Collect the sibling of the node on the path at each level, skipping
+        // levels where the node is promoted without a sibling
+        let mut level = Self::merkle_leaves::<T, E>(arr, context, prk, encode);
+        let mut path = Vec::new();
+        let mut i = index;
+        while level.len() > 1 {
+            if let Some(sibling) = level.get(i ^ 1) {
+                path.push(sibling.clone());
+            }
+            level = Self::merkle_level(level);
+            i /= 2;
+        }
+        level[0].as_mut_slice().zeroize();
+        // Reveal the blinding of the proven leaf alone
+        let mut key = Self::merkle_blinding_key(context, prk);
+        let blinding = Self::merkle_blinding::<T>(&key, index, encode);
+        key.as_mut_slice().zeroize();
+        (path, blinding)
+    }
+    pub fn merkle_verify<T>(
+        root: &[u8],
+        index: usize,
+        count: usize,
+        element: &[u8],
+        blinding: &[u8],
+        path: &[Vec<u8>],
+        encode: fn(T) -> T::Bytes,
+    ) -> bool
+    where
+        T: UnsignedInt,
+    {
+        // The blinding is of the digest length, so it cannot absorb part of
+        // the element
+        let blinding_len = <D as OutputSizeUser>::output_size();
+        if index >= count || blinding.len() != blinding_len {
+            return false;
+        }
+        // Recompute the nodes on the path from the leaf to the top
+        let mut node = Self::merkle_leaf::<T>(index, blinding, element, encode);
+        let mut path = path.iter();
+        let mut i = index;
+        let mut width = count;
+        while width > 1 {
+            // Combine with the sibling, unless the node is promoted
+            if i ^ 1 < width {
+                let Some(sibling) = path.next() else {
+                    return false;
+                };
+                if sibling.len() != node.len() {
+                    return false;
+                }
+                node = if i.is_multiple_of(2) {
+                    Self::merkle_node(&node, sibling)
+                } else {
+                    Self::merkle_node(sibling, &node)
+                };
+            }
+            i /= 2;
+            width = width.div_ceil(2);
+        }
+        // All siblings must be consumed, and the root must match
+        path.next().is_none()
+            && Self::merkle_commit::<T>(count, &node, encode)[..] == *root
+    }
+    fn merkle_leaves<T, E>(
+        arr: &[E],
+        context: &str,
+        prk: &[u8],
+        encode: fn(T) -> T::Bytes,
+    ) -> Vec<Output<D>>
+    where
+        T: UnsignedInt,
+        E: AsRef<[u8]>,
+    {
+        // Blind each leaf with a secret derived from the PRK, so that the
+        // siblings in a proof cannot be used to test guesses of an element
+        let mut key = Self::merkle_blinding_key(context, prk);
+        let leaves = arr
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                let mut blinding = Self::merkle_blinding::<T>(&key, i, encode);
+                let leaf = Self::merkle_leaf::<T>(
+                    i,
+                    &blinding,
+                    element.as_ref(),
+                    encode,
+                );
+                blinding.as_mut_slice().zeroize();
+                leaf
+            })
+            .collect();
+        key.as_mut_slice().zeroize();
+        leaves
     }
-    fn subkeys<T>(
-        arr: &[Vec<u8>],
+    fn merkle_blinding_key(context: &str, prk: &[u8]) -> Output<D> {
+        Self::expand(prk, &[context.as_bytes(), b"-BLINDING"])
+    }
+    fn merkle_blinding<T>(
         key: &[u8],
-        commit: &[u8],
-        encode: fn(T) -> Vec<u8>,
-    ) -> Vec<Vec<u8>>
+        i: usize,
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
     where
         T: UnsignedInt,
     {
-        // Allocate subkeys buffer
-        let mut k_s = Vec::with_capacity(arr.len());
-        // Derive a subkey for each element
-        for i in 0..arr.len() {
-            // Initialize MAC using key
-            let mut mac = Hmac::<D>::new_from_slice(&key)
-                .expect("HMAC can take key of any size");
-            // Absorb domain byte, element properties, and commitment
-            Mac::update(&mut mac, &[D_3]);
-            Mac::update(&mut mac, &encode(T::from_usize(i)));
-            Mac::update(&mut mac, &encode(T::from_usize(arr[i].len())));
-            Mac::update(&mut mac, &arr[i]);
-            Mac::update(&mut mac, commit);
-            // Use MAC digest as the subkey for the current element
-            let subkey: Vec<u8> = mac.finalize().into_bytes().to_vec();
-            k_s.push(subkey);
+        // Derive the blinding of the leaf at the element position
+        let mut mac = Hmac::<D>::new_from_slice(key)
+            .expect("HMAC can take key of any size");
+        Mac::update(&mut mac, &[D_12]);
+        Mac::update(&mut mac, encode(T::from_usize(i)).as_ref());
+        Self::mac_output(mac)
+    }
+    fn merkle_leaf<T>(
+        i: usize,
+        blinding: &[u8],
+        element: &[u8],
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
+    where
+        T: UnsignedInt,
+    {
+        // Commit the leaf to the element position, blinding, length, and
+        // content
+        let mut hasher = D::new();
+        hasher.update([D_8]);
+        hasher.update(encode(T::from_usize(i)));
+        hasher.update(blinding);
+        hasher.update(encode(T::from_usize(element.len())));
+        hasher.update(element);
+        hasher.finalize()
+    }
+    fn merkle_node(left: &[u8], right: &[u8]) -> Output<D> {
+        let mut hasher = D::new();
+        hasher.update([D_9]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize()
+    }
+    fn merkle_level(mut level: Vec<Output<D>>) -> Vec<Output<D>> {
+        // Combine pairs of nodes, promoting a trailing unpaired node
+        let next = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => Self::merkle_node(left, right),
+                _ => pair[0].clone(),
+            })
+            .collect();
+        for node in &mut level {
+            node.as_mut_slice().zeroize();
         }
-        k_s
+        next
+    }
+    fn merkle_commit<T>(
+        count: usize,
+        top: &[u8],
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
+    where
+        T: UnsignedInt,
+    {
+        let mut hasher = D::new();
+        hasher.update([D_10]);
+        hasher.update(encode(T::from_usize(count)));
+        hasher.update(top);
+        hasher.finalize()
     }
-    fn indices<T>(
+    fn subkey<T>(
         key: &[u8],
+        i: usize,
+        element: &[u8],
         commit: &[u8],
-        n: T,
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
+    where
+        T: UnsignedInt,
+    {
+        // Initialize MAC using key
+        let mut mac = Hmac::<D>::new_from_slice(key)
+            .expect("HMAC can take key of any size");
+        // Absorb domain byte, element properties, and commitment
+        Mac::update(&mut mac, &[D_3]);
+        Mac::update(&mut mac, encode(T::from_usize(i)).as_ref());
+        Mac::update(&mut mac, encode(T::from_usize(element.len())).as_ref());
+        Mac::update(&mut mac, element);
+        Mac::update(&mut mac, commit);
+        // Use MAC digest as the subkey for the element
+        Self::mac_output(mac)
+    }
+    fn indices<'a, T>(
+        key: &[u8],
+        commit: &[u8],
+        k_i: &'a mut [T],
         s: T,
         counter: T,
-        encode: fn(T) -> Vec<u8>,
+        encode: fn(T) -> T::Bytes,
         decode: fn(&[u8]) -> T,
-    ) -> Vec<T>
+    ) -> &'a [T]
     where
         T: UnsignedInt,
     {
         // Initialize indices array [0..n-1]
-        let n_usize = n.as_usize();
+        let n = T::from_usize(k_i.len());
         let s_usize = s.as_usize();
-        let mut k_i: Vec<T> = (0..n_usize).map(|i| T::from_usize(i)).collect();
+        for (i, k) in k_i.iter_mut().enumerate() {
+            *k = T::from_usize(i);
+        }
         // Encode external counter
         let ctr_bytes_ext = encode(counter);
         // Initialize internal counter
         let mut ctr: T = T::from(0);
         // Byte source from PRF closure
-        // Produces the next 32 bytes of PRF output on each call
+        // Produces the next digest length bytes of PRF output on each call
         let mut next = || {
             // Encode internal counter
             let ctr_bytes_in = encode(ctr);
@@ -269,144 +767,175 @@ where
             // Absorb domain byte, commitment and counters
             Mac::update(&mut mac, &[D_4]);
             Mac::update(&mut mac, commit);
-            Mac::update(&mut mac, &ctr_bytes_ext);
-            Mac::update(&mut mac, &ctr_bytes_in);
+            Mac::update(&mut mac, ctr_bytes_ext.as_ref());
+            Mac::update(&mut mac, ctr_bytes_in.as_ref());
             // Return MAC digest as PRF bytes
-            let bytes: Vec<u8> = mac.finalize().into_bytes().to_vec();
-            bytes
+            Self::mac_output(mac)
+        };
+        // Buffer of PRF bytes, and position of the next unread byte
+        let mut p = Output::<D>::default();
+        let mut pos = p.len();
+        // Draw the next integer from the PRF bytes, at the full width of T
+        let mut draw = || {
+            // Verify enough bytes available
+            if p.len() - pos < T::SIZE {
+                p.as_mut_slice().zeroize();
+                p = next();
+                pos = 0;
+            }
+            // Read integer from p, consume bytes
+            let v = decode(&p[pos..pos + T::SIZE]);
+            pos += T::SIZE;
+            v
         };
-        // Buffer of PRF bytes
-        let mut p: Vec<u8> = Vec::new();
         // Iterate until reaching subset size
         for i in 0..s_usize {
             let i_c = T::from_usize(i);
             // Limit range to unsigned integer limit
             let range = n.wrapping_sub(i_c);
-            // Calculate remainder for rejection sampling
+            // Calculate remainder for rejection sampling, all arithmetic is
+            // performed in T so the result is independent of the host width
             let rem = T::from(0).wrapping_sub(range) % range;
-            // Draw v with rejection sampling
-            let mut v: T;
-            if rem == T::from(0) {
-                // Range divides integer limit evenly, no rejection needed
-                // Verify enough bytes available
-                if p.len() < T::SIZE {
-                    p = next();
-                }
-                // Read integer from p
-                v = decode(&p[0..T::SIZE]);
-                // Consume bytes
-                p.drain(0..T::SIZE);
-            } else {
-                // Apply rejection sampling to avoid modulo bias
-                let limit = T::from(0).wrapping_sub(rem);
-                loop {
-                    // Verify enough bytes available
-                    if p.len() < T::SIZE {
-                        p = next();
-                    }
-                    // Read integer from p
-                    v = decode(&p[0..T::SIZE]);
-                    // Consume bytes
-                    p.drain(0..T::SIZE);
-                    // Only accept v in range [0, limit)
-                    if v.as_usize() < limit.as_usize() {
-                        break;
-                    }
-                    // If v is out of range, draw another value
+            // Largest multiple of range representable in T, or zero if range
+            // divides the integer limit evenly and no rejection is needed
+            let limit = T::from(0).wrapping_sub(rem);
+            // Draw v with rejection sampling to avoid modulo bias
+            let v = loop {
+                let v = draw();
+                // Only accept v in range [0, limit)
+                if rem == T::from(0) || v < limit {
+                    break v;
                 }
-            }
-            // Map v into the indices array by modular reduction
-            // of the remaining range size
+                // If v is out of range, draw another value
+            };
+            // Map v into the indices array by modular reduction of the
+            // remaining range size, which is below the array length
             let j_usize = i + (v % range).as_usize();
             // Swap indices i and j
             k_i.swap(i, j_usize);
         }
-        k_i[0..s_usize].to_vec()
+        p.as_mut_slice().zeroize();
+        &k_i[0..s_usize]
     }
-    fn combine<T>(
-        subkeys: &Vec<Vec<u8>>,
-        indices: &Vec<T>,
+    fn combine<T, E>(
+        arr: &[E],
+        key: &[u8],
+        indices: &[T],
         commit: &[u8],
         counter: T,
-        encode: fn(T) -> Vec<u8>,
-    ) -> Vec<u8>
+        encode: fn(T) -> T::Bytes,
+    ) -> Output<D>
     where
         T: UnsignedInt,
+        E: AsRef<[u8]>,
     {
-        // Set output length based on hashing algorithm
-        let output_len = <D as OutputSizeUser>::output_size();
         // Encode external counter
         let ctr_bytes = encode(counter);
-        // Allocate XOR accumulator
-        let mut acc = vec![0u8; output_len];
+        // XOR accumulator of the digest length
+        let mut acc = Output::<D>::default();
         // For all selected indices
         for i in indices.iter() {
+            // Derive the subkey for the selected element
+            let i = i.as_usize();
+            let mut subkey =
+                Self::subkey::<T>(key, i, arr[i].as_ref(), commit, encode);
             // Initialize MAC using subkey
-            let mut mac = Hmac::<D>::new_from_slice(&subkeys[i.as_usize()])
+            let mut mac = Hmac::<D>::new_from_slice(&subkey)
                 .expect("HMAC can take key of any size");
             // Absorb commitment and counter
             Mac::update(&mut mac, &[D_5]);
             Mac::update(&mut mac, commit);
-            Mac::update(&mut mac, &ctr_bytes);
-            let y = mac.finalize().into_bytes().to_vec();
+            Mac::update(&mut mac, ctr_bytes.as_ref());
+            let mut y = mac.finalize().into_bytes();
             // acc ^= Y
-            for j in 0..output_len {
-                acc[j] ^= y[j]
+            for (a, b) in acc.iter_mut().zip(y.iter()) {
+                *a ^= b
             }
+            subkey.as_mut_slice().zeroize();
+            y.as_mut_slice().zeroize();
         }
         acc
     }
-    fn derive_key_nonce<T>(
+    #[allow(clippy::too_many_arguments)]
+    fn derive_key_nonce<T, S>(
         key: &[u8],
         commit: &[u8],
         counter: T,
         acc: &[u8],
-        encode: fn(T) -> Vec<u8>,
-    ) -> (Vec<u8>, [u8; 16])
-    where
+        input: &[u8],
+        encode: fn(T) -> T::Bytes,
+        prf_key: &mut [u8],
+        nonce: &mut [u8],
+    ) where
         T: UnsignedInt,
+        S: StreamBackend,
     {
-        // Set PRF key length based on hashing algorithm
-        let digest_len = <D as OutputSizeUser>::output_size();
-        let key_len = if digest_len >= 32 {
-            32 // AES-256
-        } else if digest_len >= 24 {
-            24 // AES-196
-        } else {
-            16 // AES-128
-        };
         // Encode external counter
         let ctr_bytes = encode(counter);
         // Derive PRF key
-        // Depends on commitment, counter, and accumulator
+        // Depends on commitment, counter, accumulator, and additional input
         let mut mac = Hmac::<D>::new_from_slice(key)
             .expect("HMAC can take key of any size");
         Mac::update(&mut mac, &[D_6]);
         Mac::update(&mut mac, commit);
-        Mac::update(&mut mac, &ctr_bytes);
+        Mac::update(&mut mac, ctr_bytes.as_ref());
         Mac::update(&mut mac, acc);
-        let key_full: Vec<u8> = mac.finalize().into_bytes().to_vec();
-        let mut prf_key = vec![0u8; key_len];
+        Self::absorb_input::<T>(&mut mac, input, encode);
+        let mut key_full = mac.finalize().into_bytes();
+        let key_len = prf_key.len();
         if key_full.len() >= key_len {
             prf_key.copy_from_slice(&key_full[0..key_len]);
         } else {
-            // If hash output is too small, expand it with HKDF
+            // If hash output is too small, expand it with HKDF, labelled by
+            // the backend so that keys for different backends are separated
             prf_key[0..key_full.len()].copy_from_slice(&key_full);
             let hk = Hkdf::<D>::new(None, &key_full);
-            hk.expand(b"AES_KEY_EXPANSION", &mut prf_key[key_full.len()..])
-                .expect("HKDF expansion should succeed");
+            hk.expand_multi_info(
+                &[S::NAME.as_bytes(), b"_KEY_EXPANSION"],
+                &mut prf_key[key_full.len()..],
+            )
+            .expect("HKDF expansion should succeed");
         }
+        key_full.as_mut_slice().zeroize();
         // Derive PRF nonce
-        // Depends on commitment and counter
+        // Depends on commitment, counter, and additional input
         let mut mac = Hmac::<D>::new_from_slice(key)
             .expect("HMAC can take key of any size");
         Mac::update(&mut mac, &[D_7]);
         Mac::update(&mut mac, commit);
-        Mac::update(&mut mac, &ctr_bytes);
-        let nonce_full: Vec<u8> = mac.finalize().into_bytes().to_vec();
-        let mut nonce = [0u8; 16];
-        nonce.copy_from_slice(&nonce_full[0..16]);
-        // Return PRF key and nonce
-        (prf_key, nonce)
+        Mac::update(&mut mac, ctr_bytes.as_ref());
+        Self::absorb_input::<T>(&mut mac, input, encode);
+        let mut nonce_full = mac.finalize().into_bytes();
+        let nonce_len = nonce.len();
+        nonce.copy_from_slice(&nonce_full[0..nonce_len]);
+        nonce_full.as_mut_slice().zeroize();
+    }
+    fn absorb_input<T>(
+        mac: &mut Hmac<D>,
+        input: &[u8],
+        encode: fn(T) -> T::Bytes,
+    ) where
+        T: UnsignedInt,
+    {
+        // Absorb domain byte, length, and contents of additional input. Empty
+        // input is not absorbed, leaving output without input unchanged
+        if !input.is_empty() {
+            Mac::update(mac, &[D_11]);
+            Mac::update(mac, encode(T::from_usize(input.len())).as_ref());
+            Mac::update(mac, input);
+        }
+    }
+    fn decimal(mut i: usize, buf: &mut [u8; 20]) -> &[u8] {
+        // Write the decimal digits of i to the end of the buffer
+        let mut pos = buf.len();
+        loop {
+            pos -= 1;
+            buf[pos] = b'0' + (i % 10) as u8;
+            i /= 10;
+            if i == 0 {
+                break;
+            }
+        }
+        &buf[pos..]
     }
 }
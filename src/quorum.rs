@@ -0,0 +1,266 @@
+use crate::{
+    Drbg, DrbgError, Endian,
+    backend::{AesCtr, StreamBackend},
+    merkle::{CommitmentMode, ElementProof, verify_element_proof},
+    prf::Prf,
+    seek::EvolutionMode,
+    traits::UnsignedInt,
+};
+use alloc::{string::ToString, vec, vec::Vec};
+use core::marker::PhantomData;
+use digest::{
+    Digest, HashMarker, Output, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Generator holding only part of an array of seed material, for shared
+/// custody of the seed material.
+///
+/// A [QuorumDrbg] is created from the public Merkle commitment of a full
+/// array of seed material, as returned by [Drbg::commitment_root], and the
+/// elements held by one party, each with its inclusion proof from
+/// [Drbg::prove_element]. Every element is verified against the commitment,
+/// and elements that fail verification are treated as missing. Output can
+/// only be generated with [QuorumDrbg::fill_bytes_with_elements] from an
+/// explicit set of verified elements, so any party holding the selected
+/// elements produces the same output. Every output must be seeded by at
+/// least a minimum number of elements, the threshold set on creation.
+///
+/// The keys of the generator are derived from the commitment rather than
+/// from every element, and the state never evolves, as in
+/// [EvolutionMode::Seekable]. The counter is incremented after every output.
+/// Outputs differ from those of the [Drbg] that created the commitment.
+///
+/// # Security Considerations
+/// As the keys are derived from a public commitment, the secrecy of each
+/// output rests entirely on the elements selected for it, which must together
+/// contain sufficient entropy. Outputs provide no forward secrecy: a party
+/// holding the selected elements can reproduce every output generated from
+/// them.
+///
+/// # Example
+/// ```
+/// use hex_literal::hex;
+/// use sc_drbg::{CommitmentMode, Drbg, Endian, QuorumDrbg};
+/// use sha3::Sha3_256;
+///
+/// let arr = vec![
+///     hex!("456E64204F662054686520576F726C642053756E").to_vec(),
+///     hex!("556E6D616B65207468652057696C64204C69676874").to_vec(),
+///     hex!("536166652050617373616765").to_vec(),
+/// ];
+/// let dealer = Drbg::<Sha3_256, u64>::builder()
+///     .array(&arr)
+///     .commitment(CommitmentMode::Merkle)
+///     .init(false)
+///     .build()
+///     .expect("Should create new SC_DRBG instance");
+/// let root = dealer.commitment_root();
+/// let held: Vec<_> = [0, 2]
+///     .iter()
+///     .map(|&i| (arr[i].clone(), dealer.prove_element(i).unwrap()))
+///     .collect();
+///
+/// let mut drbg = QuorumDrbg::<Sha3_256, u64>::new(
+///     &root,
+///     arr.len(),
+///     2,
+///     &held,
+///     Some("key-ceremony"),
+///     Endian::LittleEndian,
+/// )
+/// .expect("Should create new SC_DRBG instance");
+/// assert_eq!(drbg.missing_elements(), vec![1]);
+///
+/// let mut key = [0u8; 32];
+/// assert!(drbg.fill_bytes_with_elements(&[0, 2], &mut key).is_ok());
+/// assert!(drbg.fill_bytes_with_elements(&[0, 1], &mut key).is_err());
+/// assert!(drbg.fill_bytes_with_elements(&[2], &mut key).is_err());
+/// ```
+pub struct QuorumDrbg<D, T, S = AesCtr>
+where
+    D: OutputSizeUser,
+{
+    drbg: Drbg<D, T, S>,
+    threshold: usize,
+}
+
+impl<D, T, S> QuorumDrbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Create a new [QuorumDrbg] instance from a Merkle commitment and an
+    /// incomplete set of elements.
+    ///
+    /// Each element is checked with [verify_element_proof] against the
+    /// commitment, and must have a proof for an array of `count` elements
+    /// with the byte order `endian`. Elements that fail verification are not
+    /// held, and are reported by [QuorumDrbg::missing_elements]. Outputs
+    /// must be seeded by at least `threshold` elements.
+    ///
+    /// # Arguments
+    /// - `root` - Merkle commitment returned by [Drbg::commitment_root].
+    /// - `count` - Number of elements in the full array of seed material.
+    /// - `threshold` - Minimum number of elements to select for an output.
+    /// - `elements` - Elements held, each with its inclusion proof.
+    /// - `context` - Optional context string for domain separation.
+    /// - `endian` - Byte order enum for representing integers as byte arrays.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::EmptyArray] if `count` is zero,
+    /// [DrbgError::InvalidThreshold] if `threshold` is zero or exceeds
+    /// `count`, [DrbgError::InvalidCommitment] if the commitment does not
+    /// have the digest length, or [DrbgError::IndexOutOfRange] or
+    /// [DrbgError::DuplicateIndex] if the indices of the proofs are invalid.
+    /// Returns [DrbgError::DigestTooSmall] if the digest size of `D` is below
+    /// 16 bytes.
+    pub fn new(
+        root: &[u8],
+        count: usize,
+        threshold: usize,
+        elements: &[(Vec<u8>, ElementProof)],
+        context: Option<&str>,
+        endian: Endian,
+    ) -> Result<Self, DrbgError> {
+        Drbg::<D, T, S>::validate_digest()?;
+        if count == 0 {
+            return Err(DrbgError::EmptyArray);
+        }
+        if threshold == 0 || threshold > count {
+            return Err(DrbgError::InvalidThreshold { threshold, count });
+        }
+        let digest_len = <D as OutputSizeUser>::output_size();
+        if root.len() != digest_len {
+            return Err(DrbgError::InvalidCommitment {
+                expected: digest_len,
+                found: root.len(),
+            });
+        }
+        let indices: Vec<usize> =
+            elements.iter().map(|(_, proof)| proof.index).collect();
+        Drbg::<D, T, S>::validate_indices(&indices, count)?;
+        // Hold verified elements, leaving missing elements empty
+        let mut arr = vec![Vec::new(); count];
+        for (element, proof) in elements {
+            if proof.count == count
+                && proof.endian == endian
+                && !element.is_empty()
+                && verify_element_proof::<D, T>(root, element, proof)
+            {
+                arr[proof.index] = element.clone();
+            }
+        }
+        // PRK and keys from the commitment, salted with the context
+        let context = context.unwrap_or("");
+        let mut prk = Prf::<D>::quorum(context, root);
+        let keys = Prf::<D>::keys_with_commit(
+            Output::<D>::clone_from_slice(root),
+            context,
+            &prk,
+        );
+        let drbg = Drbg {
+            arr,
+            prk: prk.to_vec(),
+            keys,
+            context: context.to_string(),
+            ctr: T::from(0),
+            endian,
+            commitment: CommitmentMode::Merkle,
+            evolution: EvolutionMode::Seekable,
+            _digest: PhantomData,
+            _backend: PhantomData,
+        };
+        prk.as_mut_slice().zeroize();
+        Ok(Self { drbg, threshold })
+    }
+    /// Return the indices of elements that are not held, in ascending order.
+    pub fn missing_elements(&self) -> Vec<usize> {
+        self.drbg
+            .arr
+            .iter()
+            .enumerate()
+            .filter_map(|(i, element)| element.is_empty().then_some(i))
+            .collect()
+    }
+    /// Return the number of elements in the full array of seed material.
+    pub fn element_count(&self) -> usize {
+        self.drbg.arr.len()
+    }
+    /// Return the minimum number of elements to select for an output.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+    /// Return the Merkle commitment the elements were verified against.
+    pub fn commitment_root(&self) -> Vec<u8> {
+        self.drbg.commitment_root()
+    }
+    /// Return the current value of the counter.
+    pub fn counter(&self) -> T {
+        self.drbg.ctr
+    }
+    /// Fills a destination buffer with random bytes, seeded by an explicit
+    /// set of elements held by the [QuorumDrbg].
+    ///
+    /// The output depends only on the commitment, the context string, the
+    /// counter, and which elements are selected, so every [QuorumDrbg]
+    /// holding the selected elements generates the same output. The counter
+    /// is incremented after each call.
+    ///
+    /// # Arguments
+    /// - `indices` - Indices of the elements from the array of seed material
+    ///   to seed the generator with.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::IndexOutOfRange] or [DrbgError::DuplicateIndex] if
+    /// the indices are invalid, [DrbgError::EmptySelection] if no indices are
+    /// given, [DrbgError::BelowThreshold] if fewer indices are given than the
+    /// threshold, [DrbgError::MissingElements] if any selected element is not
+    /// held, or [DrbgError::CounterExhausted] if the counter
+    /// has reached its maximum value. The state and destination buffer are
+    /// left unchanged on error.
+    pub fn fill_bytes_with_elements(
+        &mut self,
+        indices: &[usize],
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        Drbg::<D, T, S>::validate_indices(indices, self.drbg.arr.len())?;
+        if indices.is_empty() {
+            return Err(DrbgError::EmptySelection);
+        }
+        if indices.len() < self.threshold {
+            return Err(DrbgError::BelowThreshold {
+                threshold: self.threshold,
+                found: indices.len(),
+            });
+        }
+        let missing: Vec<usize> = indices
+            .iter()
+            .copied()
+            .filter(|&i| self.drbg.arr[i].is_empty())
+            .collect();
+        if !missing.is_empty() {
+            return Err(DrbgError::MissingElements(missing));
+        }
+        self.drbg.fill_bytes_with_elements(indices, dst)
+    }
+}
+
+impl<D, T, S> ZeroizeOnDrop for QuorumDrbg<D, T, S> where D: OutputSizeUser {}
@@ -0,0 +1,167 @@
+use crate::{
+    Drbg,
+    backend::{AesCtr, StreamBackend},
+    traits::UnsignedInt,
+};
+use digest::{
+    Digest, HashMarker, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Length in bytes of the seed accepted by `SeedableRng`.
+const SEED_LEN: usize = 32;
+
+/// Generator implementing `SeedableRng` with a configurable element layout.
+///
+/// A 32 byte seed is split into `N` consecutive elements of equal length, in
+/// order, forming the array of seed material, so `N` must evenly divide 32.
+/// Element `i` holds bytes `i * 32 / N` up to `(i + 1) * 32 / N` of the seed.
+/// The new instance uses little-endian byte order and no context string, and
+/// the seed material is initialized in the same way as [Drbg::new_le].
+///
+/// As `SeedableRng` constructors take no options, the layout is fixed by the
+/// type rather than set at runtime. The array of seed material therefore
+/// depends only on the seed, and the same seed always produces the same
+/// generator, including through `seed_from_u64` and `from_rng`. The
+/// `SeedableRng` implementation of [Drbg] uses the layout of
+/// `SeededDrbg<D, T, 4, S>`.
+///
+/// # Example
+/// ```
+/// use rand_core::{RngCore, SeedableRng};
+/// use sc_drbg::SeededDrbg;
+/// use sha3::Sha3_256;
+///
+/// let mut rng = SeededDrbg::<Sha3_256, u64, 8>::from_seed([7u8; 32]);
+/// assert_eq!(rng.drbg().element_count(), 8);
+///
+/// let num = rng.next_u64();
+/// ```
+pub struct SeededDrbg<D, T, const N: usize, S = AesCtr>
+where
+    D: OutputSizeUser,
+{
+    drbg: Drbg<D, T, S>,
+}
+
+impl<D, T, const N: usize, S> SeededDrbg<D, T, N, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Return the wrapped [Drbg].
+    pub fn drbg(&self) -> &Drbg<D, T, S> {
+        &self.drbg
+    }
+    /// Consume the [SeededDrbg], returning the wrapped [Drbg].
+    pub fn into_inner(self) -> Drbg<D, T, S> {
+        self.drbg
+    }
+}
+
+impl<D, T, const N: usize, S> SeedableRng for SeededDrbg<D, T, N, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    type Seed = [u8; SEED_LEN];
+
+    /// Create a new [SeededDrbg] instance from a 32 byte seed, split into
+    /// `N` elements.
+    ///
+    /// # Panics
+    /// This method will panic if the digest size of `D` is below 16 bytes.
+    /// An `N` that does not evenly divide 32 fails to compile.
+    fn from_seed(mut seed: Self::Seed) -> Self {
+        const {
+            assert!(
+                N > 0 && SEED_LEN.is_multiple_of(N),
+                "N must evenly divide the 32 byte seed"
+            )
+        };
+        let drbg = Drbg::builder()
+            .seed(&seed, N)
+            .build()
+            .unwrap_or_else(|err| panic!("{}", err));
+        seed.zeroize();
+        Self { drbg }
+    }
+}
+
+impl<D, T, const N: usize, S> RngCore for SeededDrbg<D, T, N, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    fn next_u32(&mut self) -> u32 {
+        self.drbg.next_u32()
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.drbg.next_u64()
+    }
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.drbg.fill_bytes(dst);
+    }
+}
+
+impl<D, T, const N: usize, S> CryptoRng for SeededDrbg<D, T, N, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+}
+
+impl<D, T, const N: usize, S> ZeroizeOnDrop for SeededDrbg<D, T, N, S> where
+    D: OutputSizeUser
+{
+}
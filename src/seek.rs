@@ -0,0 +1,150 @@
+use crate::{
+    Drbg, DrbgError, backend::StreamBackend, prf::Prf, traits::UnsignedInt,
+};
+use digest::{
+    Digest, HashMarker, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+use zeroize::Zeroize;
+
+/// Scheme for evolving the state of a [Drbg] between outputs.
+///
+/// # Security Considerations
+/// With [EvolutionMode::Seekable], outputs no longer provide forward secrecy
+/// on their own. Every output since the last [Drbg::ratchet] is derived from
+/// the same state, so an attacker who learns the state can reproduce all of
+/// those outputs, both before and after the compromise, along with any output
+/// up to the next ratchet. Forward secrecy is only provided at the
+/// granularity of explicit ratchets, which should be applied as often as the
+/// application allows.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum EvolutionMode {
+    /// The state is re-mixed after every output, using the output itself, so
+    /// outputs can only be generated in order.
+    #[default]
+    Sequential,
+    /// The state only evolves on [Drbg::ratchet]. Each output depends only on
+    /// the state and the counter, and can be generated out of order with
+    /// [Drbg::output_at].
+    Seekable,
+}
+
+impl<D, T, S> Drbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Return the [EvolutionMode] of the [Drbg].
+    pub fn evolution_mode(&self) -> EvolutionMode {
+        self.evolution
+    }
+    /// Fill a destination buffer with the output at a given counter value,
+    /// seeded by a subset of elements from the [Drbg] state.
+    ///
+    /// With [EvolutionMode::Seekable], the output of
+    /// [Drbg::fill_bytes_subset] depends only on the state and the counter,
+    /// so the output at any counter value can be generated directly, without
+    /// generating the outputs before it. The output matches that of
+    /// [Drbg::fill_bytes_subset] with the same subset and buffer length, when
+    /// called with the counter at `counter`, until the next
+    /// [Drbg::ratchet]. The state and counter are left unchanged.
+    ///
+    /// # Arguments
+    /// - `counter` - Counter value of the output.
+    /// - `subset` - Number of elements from the array of seed material to seed
+    ///   the generator with. Clamped to array length.
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::SeekUnavailable] if the [Drbg] does not use
+    /// [EvolutionMode::Seekable], or [DrbgError::CounterExhausted] if
+    /// `counter` is the maximum value of the counter, which is never used to
+    /// generate output. The destination buffer is left unchanged on error.
+    pub fn output_at(
+        &self,
+        counter: T,
+        subset: usize,
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        if self.evolution != EvolutionMode::Seekable {
+            return Err(DrbgError::SeekUnavailable);
+        }
+        if counter == T::MAX {
+            return Err(DrbgError::CounterExhausted {
+                max: T::MAX.as_u128(),
+            });
+        }
+        self.generate(subset.min(self.arr.len()), counter, &[], dst);
+        Ok(())
+    }
+    /// Fill a destination buffer with the output at a given counter value,
+    /// without the keys cached for the state.
+    ///
+    /// Recomputes the commitment, the keys expanded from the PRK, and the
+    /// subkey of every element on each call, as before these were cached, to
+    /// benchmark the cache against. The output matches [Drbg::output_at].
+    #[cfg(feature = "bench")]
+    #[doc(hidden)]
+    pub fn output_at_uncached(
+        &self,
+        counter: T,
+        subset: usize,
+        dst: &mut [u8],
+    ) -> Result<(), DrbgError> {
+        if self.evolution != EvolutionMode::Seekable {
+            return Err(DrbgError::SeekUnavailable);
+        }
+        if counter == T::MAX {
+            return Err(DrbgError::CounterExhausted {
+                max: T::MAX.as_u128(),
+            });
+        }
+        let keys = self.uncached_keys();
+        self.generate_with(
+            &keys,
+            subset.min(self.arr.len()),
+            counter,
+            &[],
+            dst,
+        );
+        Ok(())
+    }
+    /// Ratchet the [Drbg] state forward, for forward secrecy.
+    ///
+    /// A mixing key is derived from the current PRK, one round of mixing is
+    /// applied to the array of seed material, and a new PRK is derived from
+    /// the mixed state. The counter is reset to zero, starting a new sequence
+    /// of outputs for [Drbg::output_at]. Outputs from before the ratchet can
+    /// no longer be reproduced from the new state.
+    ///
+    /// Ratcheting is required for forward secrecy with
+    /// [EvolutionMode::Seekable], and can also be used with
+    /// [EvolutionMode::Sequential].
+    pub fn ratchet(&mut self) {
+        // PRK to re-mix elements from the current PRK, salted with the context
+        let mut tmp_prk = Prf::<D>::extract(
+            &[self.context.as_bytes(), b"-RATCHET"],
+            [&self.prk[..]],
+        );
+        // Mix the array and derive the PRK for the next PRF call
+        self.evolve(&tmp_prk);
+        tmp_prk.as_mut_slice().zeroize();
+        // Reset counter
+        self.ctr = T::from(0);
+    }
+}
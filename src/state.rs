@@ -0,0 +1,367 @@
+use crate::{
+    CommitmentMode, Drbg, DrbgError, Endian, EvolutionMode,
+    backend::StreamBackend, traits::UnsignedInt,
+};
+use alloc::{string::ToString, vec::Vec};
+use core::marker::PhantomData;
+use digest::{
+    Digest, HashMarker, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+use zeroize::Zeroizing;
+
+const MAGIC: &[u8; 6] = b"SCDRBG";
+const VERSION: u8 = 1;
+const FINGERPRINT_LABEL: &[u8] = b"SC_DRBG-STATE";
+const FINGERPRINT_LEN: usize = 8;
+
+impl<D, T, S> Drbg<D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Export the [Drbg] state as a versioned binary snapshot.
+    ///
+    /// The snapshot records the array of seed material, PRK, context string,
+    /// counter, byte order, commitment mode, and evolution mode, along with
+    /// the identity of the hashing algorithm, the stream backend, and the
+    /// counter width. It can be restored with [Drbg::import_state] to resume
+    /// generation where it left off. The returned buffer is zeroized on drop,
+    /// as it contains the full secret state of the generator.
+    ///
+    /// # Format
+    /// All lengths are encoded as big-endian `u32` values, and the counter is
+    /// encoded as a big-endian integer of the counter width.
+    /// - `magic` - The bytes `SCDRBG`.
+    /// - `version` - Format version, currently `1`.
+    /// - `digest` - Digest output size as a `u32`, followed by an 8 byte
+    ///   fingerprint: the start of the digest of `SC_DRBG-STATE`.
+    /// - `backend` - Length, followed by the name of the stream backend.
+    /// - `width` - Counter width in bytes.
+    /// - `endian` - Byte order, `0` for little-endian or `1` for big-endian.
+    /// - `counter` - Counter value.
+    /// - `commitment` - Commitment mode, `0` for [CommitmentMode::Flat] or `1`
+    ///   for [CommitmentMode::Merkle].
+    /// - `evolution` - Evolution mode, `0` for [EvolutionMode::Sequential] or
+    ///   `1` for [EvolutionMode::Seekable].
+    /// - `context` - Length, followed by the UTF-8 context string.
+    /// - `prk` - Length, followed by the PRK.
+    /// - `array` - Element count, followed by the length and contents of each
+    ///   element.
+    pub fn export_state(&self) -> Zeroizing<Vec<u8>> {
+        // Reserve the full length up front, avoiding reallocations that would
+        // leave copies of the state in freed memory
+        let len = MAGIC.len()
+            + 1
+            + 4
+            + FINGERPRINT_LEN
+            + 4
+            + S::NAME.len()
+            + 2
+            + T::SIZE
+            + 2
+            + 4
+            + self.context.len()
+            + 4
+            + self.prk.len()
+            + 4
+            + self.arr.iter().map(|e| 4 + e.len()).sum::<usize>();
+        let mut out = Zeroizing::new(Vec::with_capacity(len));
+        // Header and digest identity
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.extend_from_slice(&Self::encode_len(
+            <D as OutputSizeUser>::output_size(),
+        ));
+        out.extend_from_slice(&Self::fingerprint());
+        // Stream backend identity
+        out.extend_from_slice(&Self::encode_len(S::NAME.len()));
+        out.extend_from_slice(S::NAME.as_bytes());
+        // Counter width, byte order, counter value, and commitment and
+        // evolution modes
+        out.push(T::SIZE as u8);
+        out.push(match self.endian {
+            Endian::LittleEndian => 0,
+            Endian::BigEndian => 1,
+        });
+        out.extend_from_slice(&T::to_be_bytes(self.ctr));
+        out.push(match self.commitment {
+            CommitmentMode::Flat => 0,
+            CommitmentMode::Merkle => 1,
+        });
+        out.push(match self.evolution {
+            EvolutionMode::Sequential => 0,
+            EvolutionMode::Seekable => 1,
+        });
+        // Context string and PRK
+        out.extend_from_slice(&Self::encode_len(self.context.len()));
+        out.extend_from_slice(self.context.as_bytes());
+        out.extend_from_slice(&Self::encode_len(self.prk.len()));
+        out.extend_from_slice(&self.prk);
+        // Array of seed material
+        out.extend_from_slice(&Self::encode_len(self.arr.len()));
+        for element in &self.arr {
+            out.extend_from_slice(&Self::encode_len(element.len()));
+            out.extend_from_slice(element);
+        }
+        out
+    }
+    /// Restore a [Drbg] instance from a snapshot created by
+    /// [Drbg::export_state].
+    ///
+    /// The restored instance continues generating the same outputs the
+    /// exported instance would have produced.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::InvalidState] if the snapshot is malformed,
+    /// [DrbgError::UnsupportedStateVersion] if the format version is unknown,
+    /// or [DrbgError::DigestMismatch], [DrbgError::BackendMismatch], or
+    /// [DrbgError::CounterMismatch] if the snapshot was exported from a [Drbg]
+    /// with a different hashing algorithm, stream backend, or counter width.
+    pub fn import_state(state: &[u8]) -> Result<Self, DrbgError> {
+        Self::validate_digest()?;
+        let mut reader = Reader { buf: state };
+        // Header and digest identity
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(DrbgError::InvalidState);
+        }
+        let version = reader.take(1)?[0];
+        if version != VERSION {
+            return Err(DrbgError::UnsupportedStateVersion(version));
+        }
+        let digest_len = reader.take_len()?;
+        let fingerprint = reader.take(FINGERPRINT_LEN)?;
+        if digest_len != <D as OutputSizeUser>::output_size()
+            || fingerprint != Self::fingerprint()
+        {
+            return Err(DrbgError::DigestMismatch);
+        }
+        // Stream backend identity
+        let backend_len = reader.take_len()?;
+        if reader.take(backend_len)? != S::NAME.as_bytes() {
+            return Err(DrbgError::BackendMismatch);
+        }
+        // Counter width, byte order, counter value, and commitment and
+        // evolution modes
+        let width = reader.take(1)?[0] as usize;
+        if width != T::SIZE {
+            return Err(DrbgError::CounterMismatch {
+                expected: T::SIZE,
+                found: width,
+            });
+        }
+        let endian = match reader.take(1)?[0] {
+            0 => Endian::LittleEndian,
+            1 => Endian::BigEndian,
+            _ => return Err(DrbgError::InvalidState),
+        };
+        let ctr = T::from_be_bytes(reader.take(T::SIZE)?);
+        let commitment = match reader.take(1)?[0] {
+            0 => CommitmentMode::Flat,
+            1 => CommitmentMode::Merkle,
+            _ => return Err(DrbgError::InvalidState),
+        };
+        let evolution = match reader.take(1)?[0] {
+            0 => EvolutionMode::Sequential,
+            1 => EvolutionMode::Seekable,
+            _ => return Err(DrbgError::InvalidState),
+        };
+        // Context string and PRK
+        let context_len = reader.take_len()?;
+        let context = core::str::from_utf8(reader.take(context_len)?)
+            .map_err(|_| DrbgError::InvalidState)?;
+        let prk_len = reader.take_len()?;
+        if prk_len != <D as OutputSizeUser>::output_size() {
+            return Err(DrbgError::InvalidState);
+        }
+        let mut prk = Zeroizing::new(reader.take(prk_len)?.to_vec());
+        // Array of seed material, zeroized if the snapshot is rejected
+        let count = reader.take_len()?;
+        let mut arr =
+            Zeroizing::new(Vec::with_capacity(count.min(reader.buf.len())));
+        for _ in 0..count {
+            let element_len = reader.take_len()?;
+            arr.push(reader.take(element_len)?.to_vec());
+        }
+        if !reader.buf.is_empty() {
+            return Err(DrbgError::InvalidState);
+        }
+        // Validate the array before deriving keys, as the Merkle commitment
+        // is undefined for an empty array
+        Self::validate_array(&arr)?;
+        let keys = Self::state_keys(&arr, context, &prk, endian, commitment);
+        let drbg = Self {
+            arr: core::mem::take(&mut arr),
+            prk: core::mem::take(&mut prk),
+            keys,
+            context: context.to_string(),
+            ctr,
+            endian,
+            commitment,
+            evolution,
+            _digest: PhantomData,
+            _backend: PhantomData,
+        };
+        Ok(drbg)
+    }
+    fn fingerprint() -> [u8; FINGERPRINT_LEN] {
+        let digest = D::digest(FINGERPRINT_LABEL);
+        let mut fingerprint = [0u8; FINGERPRINT_LEN];
+        fingerprint.copy_from_slice(&digest[..FINGERPRINT_LEN]);
+        fingerprint
+    }
+    fn encode_len(len: usize) -> [u8; 4] {
+        u32::try_from(len)
+            .expect("length should fit in a u32")
+            .to_be_bytes()
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DrbgError> {
+        if self.buf.len() < len {
+            return Err(DrbgError::InvalidState);
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+    fn take_len(&mut self) -> Result<usize, DrbgError> {
+        let bytes = self.take(4)?;
+        let len = u32::from_be_bytes(
+            bytes.try_into().expect("slice must be exactly 4 bytes"),
+        );
+        Ok(len as usize)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{
+        Deserialize, Deserializer, Serialize, Serializer,
+        de::{self, SeqAccess, Visitor},
+    };
+
+    const MAX_SEQ_HINT: usize = 1 << 16;
+    const MIN_SEQ_CAPACITY: usize = 256;
+
+    impl<D, T, S> Serialize for Drbg<D, T, S>
+    where
+        D: Digest + CoreProxy + OutputSizeUser,
+        D::Core: Sync
+            + HashMarker
+            + UpdateCore
+            + FixedOutputCore
+            + BufferKindUser<BufferKind = Eager>
+            + Default
+            + Clone
+            + BlockSizeUser,
+        <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+        Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+        T: UnsignedInt,
+        S: StreamBackend,
+    {
+        /// Serialize the [Drbg] state as the bytes of
+        /// [Drbg::export_state].
+        fn serialize<Se: Serializer>(
+            &self,
+            serializer: Se,
+        ) -> Result<Se::Ok, Se::Error> {
+            serializer.serialize_bytes(&self.export_state())
+        }
+    }
+
+    impl<'de, D, T, S> Deserialize<'de> for Drbg<D, T, S>
+    where
+        D: Digest + CoreProxy + OutputSizeUser,
+        D::Core: Sync
+            + HashMarker
+            + UpdateCore
+            + FixedOutputCore
+            + BufferKindUser<BufferKind = Eager>
+            + Default
+            + Clone
+            + BlockSizeUser,
+        <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+        Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+        T: UnsignedInt,
+        S: StreamBackend,
+    {
+        /// Deserialize the [Drbg] state from the bytes of
+        /// [Drbg::export_state].
+        fn deserialize<De: Deserializer<'de>>(
+            deserializer: De,
+        ) -> Result<Self, De::Error> {
+            deserializer.deserialize_bytes(StateVisitor(PhantomData))
+        }
+    }
+
+    struct StateVisitor<D, T, S>(PhantomData<(D, T, S)>);
+
+    impl<'de, D, T, S> Visitor<'de> for StateVisitor<D, T, S>
+    where
+        D: Digest + CoreProxy + OutputSizeUser,
+        D::Core: Sync
+            + HashMarker
+            + UpdateCore
+            + FixedOutputCore
+            + BufferKindUser<BufferKind = Eager>
+            + Default
+            + Clone
+            + BlockSizeUser,
+        <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+        Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+        T: UnsignedInt,
+        S: StreamBackend,
+    {
+        type Value = Drbg<D, T, S>;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(f, "an SC_DRBG state snapshot")
+        }
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Drbg::import_state(v).map_err(E::custom)
+        }
+        fn visit_seq<A: SeqAccess<'de>>(
+            self,
+            mut seq: A,
+        ) -> Result<Self::Value, A::Error> {
+            // Reserve up front from the size hint, capped against hostile
+            // hints, and grow into a new buffer when full, so that the
+            // previous buffer is wiped on drop rather than freed by a
+            // reallocation
+            let capacity = seq.size_hint().unwrap_or(0).min(MAX_SEQ_HINT);
+            let mut state = Zeroizing::new(Vec::with_capacity(capacity));
+            while let Some(byte) = seq.next_element::<u8>()? {
+                if state.len() == state.capacity() {
+                    let grown = (state.capacity() * 2).max(MIN_SEQ_CAPACITY);
+                    let mut next = Zeroizing::new(Vec::with_capacity(grown));
+                    next.extend_from_slice(&state);
+                    state = next;
+                }
+                state.push(byte);
+            }
+            Drbg::import_state(&state).map_err(de::Error::custom)
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    Drbg, DrbgError,
+    backend::{AesCtr, StreamBackend},
+    traits::UnsignedInt,
+};
+use alloc::{vec, vec::Vec};
+use digest::{
+    Digest, HashMarker, OutputSizeUser,
+    block_buffer::Eager,
+    core_api::{
+        BlockSizeUser, BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore,
+    },
+    typenum::{IsLess, Le, NonZero, U256},
+};
+#[cfg(feature = "std")]
+use std::io;
+use zeroize::Zeroizing;
+
+/// Streaming adapter for generating large outputs from a [Drbg], returned by
+/// [Drbg::stream].
+///
+/// Output is generated in segments of a fixed length, each produced from a
+/// single state with one PRF call, and the forward secrecy update is applied
+/// once per segment rather than once per read. Implements [Iterator] yielding
+/// the output in chunks of at most one segment, and `std::io::Read` with the
+/// `std` feature enabled.
+///
+/// # Determinism
+/// The output stream is identical to the concatenation of the outputs of
+/// repeated [Drbg::fill_bytes_subset] calls using all elements, with a
+/// destination buffer of the segment length. Reading `n` segments from a
+/// stream leaves the [Drbg] in the same state as `n` such calls, regardless of
+/// how the reads are split. A segment is generated as soon as its first byte
+/// is read, so bytes of a partially read segment are discarded when the
+/// stream is dropped.
+pub struct DrbgStream<'a, D, T, S = AesCtr>
+where
+    D: OutputSizeUser,
+{
+    drbg: &'a mut Drbg<D, T, S>,
+    buf: Zeroizing<Vec<u8>>,
+    pos: usize,
+    segment_len: usize,
+}
+
+impl<'a, D, T, S> DrbgStream<'a, D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    pub(crate) fn new(drbg: &'a mut Drbg<D, T, S>, segment_len: usize) -> Self {
+        Self {
+            drbg,
+            buf: Zeroizing::new(Vec::new()),
+            pos: 0,
+            segment_len: segment_len.max(1),
+        }
+    }
+    /// Return the segment length in bytes.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+    /// Fill a destination buffer with the next bytes of the stream.
+    ///
+    /// Fallible variant of `std::io::Read::read`, always filling the whole
+    /// destination buffer. Whole segments are generated directly into the
+    /// destination buffer, and partial segments are buffered.
+    ///
+    /// # Arguments
+    /// - `dst` - Destination buffer to fill with random bytes.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::CounterExhausted] if the counter reaches its
+    /// maximum value. Bytes generated before the error are written to the
+    /// start of the destination buffer.
+    pub fn try_fill(&mut self, dst: &mut [u8]) -> Result<(), DrbgError> {
+        let mut filled = 0;
+        while filled < dst.len() {
+            filled += self.step(&mut dst[filled..])?;
+        }
+        Ok(())
+    }
+    fn step(&mut self, dst: &mut [u8]) -> Result<usize, DrbgError> {
+        let subset = self.drbg.arr.len();
+        if self.pos < self.buf.len() {
+            // Serve buffered bytes from the current segment
+            let n = dst.len().min(self.buf.len() - self.pos);
+            dst[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        } else if dst.len() >= self.segment_len {
+            // Generate a whole segment directly into the destination
+            self.drbg
+                .try_fill_bytes_subset(subset, &mut dst[..self.segment_len])?;
+            Ok(self.segment_len)
+        } else {
+            // Generate the next segment into the buffer, kept marked as read
+            // until generation succeeds
+            self.buf.resize(self.segment_len, 0);
+            self.pos = self.buf.len();
+            self.drbg.try_fill_bytes_subset(subset, &mut self.buf)?;
+            self.pos = 0;
+            Ok(0)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<D, T, S> io::Read for DrbgStream<'_, D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    /// Fill the buffer with the next bytes of the stream.
+    ///
+    /// Always fills the whole buffer, unless the counter is exhausted. Returns
+    /// an error of kind [io::ErrorKind::Other] wrapping
+    /// [DrbgError::CounterExhausted] if no bytes could be generated.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.step(&mut buf[filled..]) {
+                Ok(n) => filled += n,
+                // Report the bytes generated before the counter was exhausted
+                Err(_) if filled > 0 => break,
+                Err(err) => return Err(io::Error::other(err)),
+            }
+        }
+        Ok(filled)
+    }
+}
+
+impl<D, T, S> Iterator for DrbgStream<'_, D, T, S>
+where
+    D: Digest + CoreProxy + OutputSizeUser,
+    D::Core: Sync
+        + HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone
+        + BlockSizeUser,
+    <D::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<D::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+    T: UnsignedInt,
+    S: StreamBackend,
+{
+    type Item = Vec<u8>;
+
+    /// Return the next chunk of the stream.
+    ///
+    /// Returns the rest of the current segment if it was partially read,
+    /// otherwise the next whole segment. Returns `None` once the counter is
+    /// exhausted.
+    fn next(&mut self) -> Option<Vec<u8>> {
+        let len = if self.pos < self.buf.len() {
+            self.buf.len() - self.pos
+        } else {
+            self.segment_len
+        };
+        let mut chunk = vec![0u8; len];
+        self.try_fill(&mut chunk).ok()?;
+        Some(chunk)
+    }
+}
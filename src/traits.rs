@@ -1,26 +1,37 @@
-use std::{cmp::PartialEq, ops::Rem};
+use alloc::vec::Vec;
+use core::{
+    cmp::{PartialEq, PartialOrd},
+    ops::Rem,
+};
 
 /// A trait for unsigned integers, providing common arithmetic and byte
 /// conversion functionality.
 ///
-/// Abstracts over `u32` and `u64`, enabling generic code that works with
-/// different unsigned integer types.
+/// Abstracts over `u32`, `u64`, and `u128`, enabling generic code that works
+/// with different unsigned integer types. Integers can be encoded either into a
+/// heap allocated `Vec<u8>`, or into the fixed-size array type `Bytes` for
+/// allocation-free use.
 pub trait UnsignedInt:
-    Copy + From<u32> + Rem<Output = Self> + PartialEq
+    Copy + From<u32> + Rem<Output = Self> + PartialEq + PartialOrd
 {
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Copy + Default;
     const MAX: Self;
     const SIZE: usize;
     fn wrapping_add(self, other: Self) -> Self;
     fn wrapping_sub(self, other: Self) -> Self;
     fn to_le_bytes(self) -> Vec<u8>;
     fn to_be_bytes(self) -> Vec<u8>;
+    fn to_le_array(self) -> Self::Bytes;
+    fn to_be_array(self) -> Self::Bytes;
     fn from_le_bytes(bytes: &[u8]) -> Self;
     fn from_be_bytes(bytes: &[u8]) -> Self;
     fn as_usize(self) -> usize;
+    fn as_u128(self) -> u128;
     fn from_usize(v: usize) -> Self;
 }
 
 impl UnsignedInt for u32 {
+    type Bytes = [u8; 4];
     const MAX: u32 = u32::MAX;
     const SIZE: usize = 4;
     fn wrapping_add(self, other: Self) -> Self {
@@ -35,6 +46,12 @@ impl UnsignedInt for u32 {
     fn to_be_bytes(self) -> Vec<u8> {
         self.to_be_bytes().to_vec()
     }
+    fn to_le_array(self) -> Self::Bytes {
+        self.to_le_bytes()
+    }
+    fn to_be_array(self) -> Self::Bytes {
+        self.to_be_bytes()
+    }
     fn from_le_bytes(bytes: &[u8]) -> Self {
         u32::from_le_bytes(
             bytes[..4]
@@ -52,12 +69,16 @@ impl UnsignedInt for u32 {
     fn as_usize(self) -> usize {
         self as usize
     }
+    fn as_u128(self) -> u128 {
+        self as u128
+    }
     fn from_usize(v: usize) -> Self {
         v as u32
     }
 }
 
 impl UnsignedInt for u64 {
+    type Bytes = [u8; 8];
     const MAX: u64 = u64::MAX;
     const SIZE: usize = 8;
     fn wrapping_add(self, other: Self) -> Self {
@@ -72,6 +93,12 @@ impl UnsignedInt for u64 {
     fn to_be_bytes(self) -> Vec<u8> {
         self.to_be_bytes().to_vec()
     }
+    fn to_le_array(self) -> Self::Bytes {
+        self.to_le_bytes()
+    }
+    fn to_be_array(self) -> Self::Bytes {
+        self.to_be_bytes()
+    }
     fn from_le_bytes(bytes: &[u8]) -> Self {
         u64::from_le_bytes(
             bytes[..8]
@@ -89,7 +116,57 @@ impl UnsignedInt for u64 {
     fn as_usize(self) -> usize {
         self as usize
     }
+    fn as_u128(self) -> u128 {
+        self as u128
+    }
     fn from_usize(v: usize) -> Self {
         v as u64
     }
 }
+
+impl UnsignedInt for u128 {
+    type Bytes = [u8; 16];
+    const MAX: u128 = u128::MAX;
+    const SIZE: usize = 16;
+    fn wrapping_add(self, other: Self) -> Self {
+        self.wrapping_add(other)
+    }
+    fn wrapping_sub(self, other: Self) -> Self {
+        self.wrapping_sub(other)
+    }
+    fn to_le_bytes(self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+    fn to_be_bytes(self) -> Vec<u8> {
+        self.to_be_bytes().to_vec()
+    }
+    fn to_le_array(self) -> Self::Bytes {
+        self.to_le_bytes()
+    }
+    fn to_be_array(self) -> Self::Bytes {
+        self.to_be_bytes()
+    }
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        u128::from_le_bytes(
+            bytes[..16]
+                .try_into()
+                .expect("slice must be exactly 16 bytes for u128 conversion"),
+        )
+    }
+    fn from_be_bytes(bytes: &[u8]) -> Self {
+        u128::from_be_bytes(
+            bytes[..16]
+                .try_into()
+                .expect("slice must be exactly 16 bytes for u128 conversion"),
+        )
+    }
+    fn as_usize(self) -> usize {
+        self as usize
+    }
+    fn as_u128(self) -> u128 {
+        self
+    }
+    fn from_usize(v: usize) -> Self {
+        v as u128
+    }
+}
@@ -0,0 +1,436 @@
+use crate::{Algorithm, DrbgError, DynDrbg, EvolutionMode};
+use serde::{Deserialize, Serialize};
+use std::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// File of known-answer test vectors for SC_DRBG.
+///
+/// Vector files are JSON documents, intended for checking the conformance of
+/// other implementations against this crate. All byte strings are hex
+/// encoded, and integer outputs are hex encoded in big-endian byte order with
+/// the full width of the integer type.
+///
+/// # Format
+/// - `description` - Free-form description of the vectors in the file.
+/// - `vectors` - Array of test vectors, each with the fields:
+///   - `name` - Name identifying the vector.
+///   - `digest` - Hashing algorithm, as accepted by
+///     [DigestAlgorithm](crate::DigestAlgorithm), such as `SHA3-256`.
+///   - `counter` - Counter width, as `u32`, `u64`, or `u128`.
+///   - `endian` - Byte order, as `LE` or `BE`.
+///   - `seed` - Array of seed elements.
+///   - `context` - Context string, or `null` for no context.
+///   - `init` - Whether seed elements are processed by the initialization
+///     function.
+///   - `nonce` - Initialization nonce, or `null` for the default hash of the
+///     seed elements. Only used when `init` is `true`.
+///   - `rounds` - Number of mixing rounds, `1` when omitted. Only used when
+///     `init` is `true`.
+///   - `seekable` - Whether the generator uses the seekable evolution mode,
+///     `false` when omitted.
+///   - `calls` - Sequence of calls made on one generator, in order.
+///
+/// Each call has an `op` field selecting one of:
+/// - `next_u32` and `next_u64` - Generate an integer from `subset` elements,
+///   or all elements when `subset` is omitted, with the `expected` output.
+/// - `fill_bytes` - Generate `len` bytes from `subset` elements, or all
+///   elements when `subset` is omitted, with the `expected` output.
+/// - `fill_bytes_with_input` - Generate `len` bytes from `subset` elements,
+///   or all elements when `subset` is omitted, bound to the additional
+///   `input`, with the `expected` output.
+/// - `fill_bytes_with_elements` - Generate `len` bytes from the elements at
+///   `indices`, with the `expected` output.
+/// - `reseed` - Reseed with the `additional` seed elements and an optional
+///   `label`. Produces no output.
+/// - `fork` - Fork a child generator with the `label`, and generate `len`
+///   bytes from all elements of the child, with the `expected` output. The
+///   child is then discarded, and later calls continue on the parent.
+/// - `output_at` - Generate `len` bytes from `subset` elements, or all
+///   elements when `subset` is omitted, at the given `counter` value, with
+///   the `expected` output. Only valid in seekable vectors.
+/// - `ratchet` - Ratchet the state forward. Produces no output.
+///
+/// Vectors use the [AesCtr](crate::AesCtr) stream backend.
+///
+/// # Example
+/// ```json
+/// {
+///   "description": "SC_DRBG example vectors",
+///   "vectors": [
+///     {
+///       "name": "sha3-256-u64-le",
+///       "digest": "SHA3-256",
+///       "counter": "u64",
+///       "endian": "LE",
+///       "seed": ["ca33496c5c9e5f3c", "e17baaae2056f7ce"],
+///       "context": "some-test-app",
+///       "init": true,
+///       "nonce": null,
+///       "rounds": 1,
+///       "calls": [
+///         { "op": "next_u64", "subset": 1, "expected": "..." },
+///         { "op": "fill_bytes", "len": 16, "expected": "..." }
+///       ]
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VectorFile {
+    /// Free-form description of the vectors in the file.
+    pub description: String,
+    /// Test vectors in the file.
+    pub vectors: Vec<TestVector>,
+}
+
+/// Known-answer test vector for one SC_DRBG configuration.
+///
+/// See [VectorFile] for the format.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TestVector {
+    /// Name identifying the vector.
+    pub name: String,
+    /// Hashing algorithm.
+    pub digest: String,
+    /// Counter width.
+    pub counter: String,
+    /// Byte order.
+    pub endian: String,
+    /// Hex encoded seed elements.
+    pub seed: Vec<String>,
+    /// Optional context string.
+    pub context: Option<String>,
+    /// Whether seed elements are processed by the initialization function.
+    pub init: bool,
+    /// Optional hex encoded initialization nonce.
+    pub nonce: Option<String>,
+    /// Number of mixing rounds.
+    #[serde(default = "default_rounds")]
+    pub rounds: usize,
+    /// Whether the generator uses the seekable evolution mode.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub seekable: bool,
+    /// Sequence of calls made on one generator.
+    pub calls: Vec<Call>,
+}
+
+/// Call made on a generator by a [TestVector].
+///
+/// Expected outputs are hex encoded, and may be left empty in a vector that
+/// is passed to [TestVector::generate].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Call {
+    /// Generate a `u32` from a subset of elements.
+    NextU32 {
+        /// Number of elements, or all elements when `None`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subset: Option<usize>,
+        /// Expected output, big-endian.
+        #[serde(default)]
+        expected: String,
+    },
+    /// Generate a `u64` from a subset of elements.
+    NextU64 {
+        /// Number of elements, or all elements when `None`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subset: Option<usize>,
+        /// Expected output, big-endian.
+        #[serde(default)]
+        expected: String,
+    },
+    /// Generate bytes from a subset of elements.
+    FillBytes {
+        /// Number of elements, or all elements when `None`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subset: Option<usize>,
+        /// Number of bytes to generate.
+        len: usize,
+        /// Expected output.
+        #[serde(default)]
+        expected: String,
+    },
+    /// Generate bytes from a subset of elements, bound to additional input.
+    FillBytesWithInput {
+        /// Number of elements, or all elements when `None`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subset: Option<usize>,
+        /// Hex encoded additional input.
+        input: String,
+        /// Number of bytes to generate.
+        len: usize,
+        /// Expected output.
+        #[serde(default)]
+        expected: String,
+    },
+    /// Generate bytes from an explicit set of elements.
+    FillBytesWithElements {
+        /// Indices of the selected elements.
+        indices: Vec<usize>,
+        /// Number of bytes to generate.
+        len: usize,
+        /// Expected output.
+        #[serde(default)]
+        expected: String,
+    },
+    /// Reseed with additional seed material.
+    Reseed {
+        /// Hex encoded additional seed elements.
+        additional: Vec<String>,
+        /// Optional label for domain separation between reseeds.
+        #[serde(default)]
+        label: Option<String>,
+    },
+    /// Fork a child generator, and generate bytes from the child.
+    Fork {
+        /// Label for domain separation between children.
+        label: String,
+        /// Number of bytes to generate from the child.
+        len: usize,
+        /// Expected output of the child.
+        #[serde(default)]
+        expected: String,
+    },
+    /// Generate bytes from a subset of elements at a given counter value.
+    OutputAt {
+        /// Counter value of the output.
+        counter: u64,
+        /// Number of elements, or all elements when `None`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subset: Option<usize>,
+        /// Number of bytes to generate.
+        len: usize,
+        /// Expected output.
+        #[serde(default)]
+        expected: String,
+    },
+    /// Ratchet the state forward.
+    Ratchet,
+}
+
+fn default_rounds() -> usize {
+    1
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl VectorFile {
+    /// Parse a vector file from JSON.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::InvalidVector] if the JSON is malformed.
+    pub fn from_json(json: &str) -> Result<Self, DrbgError> {
+        serde_json::from_str(json)
+            .map_err(|err| DrbgError::InvalidVector(err.to_string()))
+    }
+    /// Serialize the vector file as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self)
+            .expect("vector file should serialize to JSON")
+    }
+    /// Fill in the expected outputs of every vector in the file.
+    ///
+    /// # Errors
+    /// Returns a [DrbgError] if any vector is invalid. See
+    /// [TestVector::generate].
+    pub fn generate(&mut self) -> Result<(), DrbgError> {
+        self.vectors.iter_mut().try_for_each(TestVector::generate)
+    }
+    /// Check the expected outputs of every vector in the file.
+    ///
+    /// # Errors
+    /// Returns the first error of any vector. See [TestVector::check].
+    pub fn check(&self) -> Result<(), DrbgError> {
+        self.vectors.iter().try_for_each(TestVector::check)
+    }
+}
+
+impl TestVector {
+    /// Return the algorithm descriptor of the vector.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::InvalidAlgorithm] if the digest, counter width, or
+    /// byte order is not supported.
+    pub fn algorithm(&self) -> Result<Algorithm, DrbgError> {
+        format!("{}/{}/{}", self.digest, self.counter, self.endian).parse()
+    }
+    /// Fill in the expected outputs of the vector by running its calls.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::InvalidAlgorithm] or [DrbgError::InvalidVector] if
+    /// the vector is malformed, or any [DrbgError] returned by the calls.
+    pub fn generate(&mut self) -> Result<(), DrbgError> {
+        let outputs = self.run()?;
+        for (call, output) in self.calls.iter_mut().zip(outputs) {
+            match call {
+                Call::NextU32 { expected, .. }
+                | Call::NextU64 { expected, .. }
+                | Call::FillBytes { expected, .. }
+                | Call::FillBytesWithInput { expected, .. }
+                | Call::FillBytesWithElements { expected, .. }
+                | Call::Fork { expected, .. }
+                | Call::OutputAt { expected, .. } => {
+                    *expected = output.unwrap_or_default();
+                }
+                Call::Reseed { .. } | Call::Ratchet => {}
+            }
+        }
+        Ok(())
+    }
+    /// Check the expected outputs of the vector by running its calls.
+    ///
+    /// # Errors
+    /// Returns [DrbgError::VectorMismatch] for the first call with an output
+    /// that differs from the expected output, [DrbgError::InvalidAlgorithm]
+    /// or [DrbgError::InvalidVector] if the vector is malformed, or any
+    /// [DrbgError] returned by the calls.
+    pub fn check(&self) -> Result<(), DrbgError> {
+        let outputs = self.run()?;
+        for (i, (call, output)) in self.calls.iter().zip(outputs).enumerate() {
+            let expected = match call {
+                Call::NextU32 { expected, .. }
+                | Call::NextU64 { expected, .. }
+                | Call::FillBytes { expected, .. }
+                | Call::FillBytesWithInput { expected, .. }
+                | Call::FillBytesWithElements { expected, .. }
+                | Call::Fork { expected, .. }
+                | Call::OutputAt { expected, .. } => expected,
+                Call::Reseed { .. } | Call::Ratchet => continue,
+            };
+            let found = output.unwrap_or_default();
+            if !expected.eq_ignore_ascii_case(&found) {
+                return Err(DrbgError::VectorMismatch {
+                    name: self.name.clone(),
+                    call: i,
+                    expected: expected.clone(),
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
+    /// Run the calls of the vector on a new generator, returning the hex
+    /// encoded output of each call, or `None` for calls without output.
+    fn run(&self) -> Result<Vec<Option<String>>, DrbgError> {
+        // Parse algorithm, decode seed material and nonce
+        let algorithm = self.algorithm()?;
+        let arr = decode_all(&self.seed)?;
+        let nonce = self.nonce.as_deref().map(decode).transpose()?;
+        // Create DRBG from the vector options
+        let mut drbg = DynDrbg::with_options(
+            algorithm,
+            &arr,
+            self.context.as_deref(),
+            nonce.as_deref(),
+            self.rounds,
+            self.init,
+            if self.seekable {
+                EvolutionMode::Seekable
+            } else {
+                EvolutionMode::Sequential
+            },
+        )?;
+        // Run each call in order
+        let mut outputs = Vec::with_capacity(self.calls.len());
+        for call in &self.calls {
+            let output = match call {
+                Call::NextU32 { subset, .. } => {
+                    let subset = subset.unwrap_or(arr.len());
+                    Some(encode(
+                        &drbg.try_next_u32_subset(subset)?.to_be_bytes(),
+                    ))
+                }
+                Call::NextU64 { subset, .. } => {
+                    let subset = subset.unwrap_or(arr.len());
+                    Some(encode(
+                        &drbg.try_next_u64_subset(subset)?.to_be_bytes(),
+                    ))
+                }
+                Call::FillBytes { subset, len, .. } => {
+                    let mut bytes = vec![0u8; *len];
+                    drbg.try_fill_bytes_subset(
+                        subset.unwrap_or(arr.len()),
+                        &mut bytes,
+                    )?;
+                    Some(encode(&bytes))
+                }
+                Call::FillBytesWithInput {
+                    subset, input, len, ..
+                } => {
+                    let mut bytes = vec![0u8; *len];
+                    drbg.fill_bytes_with_input(
+                        subset.unwrap_or(arr.len()),
+                        &decode(input)?,
+                        &mut bytes,
+                    )?;
+                    Some(encode(&bytes))
+                }
+                Call::FillBytesWithElements { indices, len, .. } => {
+                    let mut bytes = vec![0u8; *len];
+                    drbg.fill_bytes_with_elements(indices, &mut bytes)?;
+                    Some(encode(&bytes))
+                }
+                Call::Reseed { additional, label } => {
+                    drbg.reseed(&decode_all(additional)?, label.as_deref())?;
+                    None
+                }
+                Call::Fork { label, len, .. } => {
+                    let mut bytes = vec![0u8; *len];
+                    drbg.fork(label)
+                        .try_fill_bytes_subset(arr.len(), &mut bytes)?;
+                    Some(encode(&bytes))
+                }
+                Call::OutputAt {
+                    counter,
+                    subset,
+                    len,
+                    ..
+                } => {
+                    let mut bytes = vec![0u8; *len];
+                    drbg.output_at(
+                        (*counter).into(),
+                        subset.unwrap_or(arr.len()),
+                        &mut bytes,
+                    )?;
+                    Some(encode(&bytes))
+                }
+                Call::Ratchet => {
+                    drbg.ratchet();
+                    None
+                }
+            };
+            outputs.push(output);
+        }
+        Ok(outputs)
+    }
+}
+
+fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode(s: &str) -> Result<Vec<u8>, DrbgError> {
+    let invalid = || DrbgError::InvalidVector(format!("invalid hex {:?}", s));
+    // Check every character, as from_str_radix accepts a leading sign
+    if !s.len().is_multiple_of(2) || !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+fn decode_all(elements: &[String]) -> Result<Vec<Vec<u8>>, DrbgError> {
+    elements.iter().map(|element| decode(element)).collect()
+}
@@ -0,0 +1,74 @@
+use rand_core::RngCore;
+use sc_drbg::{ChaCha20, Shake256Xof, StaticDrbg};
+use sha3::Sha3_256;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+struct CountingAlloc;
+
+// Only count allocations made by the test thread, not the test harness
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+}
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.alloc(layout) }
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn count_allocations(f: impl FnOnce()) -> usize {
+    ALLOCATIONS.store(0, Ordering::SeqCst);
+    COUNTING.with(|counting| counting.set(true));
+    f();
+    COUNTING.with(|counting| counting.set(false));
+    ALLOCATIONS.load(Ordering::SeqCst)
+}
+
+#[test]
+fn drbg_static_alloc_free() {
+    // Check that creating, generating, and reseeding never allocate
+    let allocations = count_allocations(|| {
+        let arr = [[0x42u8; 32]; 8];
+        let mut drbg = StaticDrbg::<Sha3_256, u64, 8, 32>::new_le(
+            &arr,
+            Some("firmware"),
+            true,
+        )
+        .expect("Should create new SC_DRBG instance");
+        let mut bytes = [0u8; 64];
+        drbg.fill_bytes_subset(3, &mut bytes);
+        drbg.fill_bytes(&mut bytes);
+        drbg.fill_bytes_with_elements(&[7, 1], &mut bytes)
+            .expect("Should fill bytes with elements");
+        drbg.reseed(&[b"fresh entropy"], Some("epoch-1"))
+            .expect("Should reseed SC_DRBG instance");
+        drbg.next_u32();
+        let mut drbg = StaticDrbg::<Sha3_256, u32, 8, 32, ChaCha20>::new_be(
+            &arr, None, true,
+        )
+        .expect("Should create new SC_DRBG instance");
+        drbg.next_u64();
+        let mut drbg = StaticDrbg::<Sha3_256, u32, 8, 32, Shake256Xof>::new_be(
+            &arr,
+            Some("firmware"),
+            false,
+        )
+        .expect("Should create new SC_DRBG instance");
+        drbg.next_u64_subset(5);
+    });
+    assert_eq!(allocations, 0);
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    fs,
+    io::{ErrorKind, Write},
+    path::Path,
+    process::{Command, Output, Stdio},
+};
+
+const SEEDS: [&str; 7] = [
+    "ca33496c5c9e5f3ce6e932a0670d320f",
+    "e17baaae2056f7cea2083482f9818b1c",
+    "2c1aef2c624598ae937eed2b5ad9448b",
+    "6932a3726327aa4a092771dabf198fc7",
+    "fe9fe0c3b16f8ae27b09856bd0f487d1",
+    "87c83f8f122b3bcccf42a97f487133f9",
+    "5bc58505a5cc3406168facc39ba0f5dc",
+];
+
+fn sc_drbg(args: &[&str], stdin: &str) -> Output {
+    // Run the binary, writing the given input to stdin. The binary may exit
+    // on invalid arguments before reading stdin, closing the pipe
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sc-drbg"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Should run sc-drbg");
+    let written = child
+        .stdin
+        .take()
+        .expect("Should open stdin")
+        .write_all(stdin.as_bytes());
+    if let Err(err) = written {
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe, "Should write stdin");
+    }
+    child.wait_with_output().expect("Should wait for sc-drbg")
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "sc-drbg failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).expect("Should output UTF-8")
+}
+
+#[test]
+fn cli_integers() {
+    // Seed elements as hex lines, with a comment and a blank line
+    let seeds = format!("# seed elements\n\n{}\n", SEEDS.join("\n"));
+    let args = ["-", "--context", "some-test-app", "--init", "-n", "3"];
+    // Check that u64 output matches the little-endian library output
+    let output = sc_drbg(
+        &[&args[..], &["--emit", "u64", "--format", "dec"]].concat(),
+        &seeds,
+    );
+    assert_eq!(
+        stdout(&output),
+        "4347230222507331714\n16466604991238817181\n12219542919680157343\n"
+    );
+    // Check that u32 output matches the big-endian library output
+    let output = sc_drbg(
+        &[
+            &args[..],
+            &["--emit", "u32", "--endian", "be", "--format", "dec"],
+        ]
+        .concat(),
+        &seeds,
+    );
+    assert_eq!(stdout(&output), "502628020\n2880383839\n3798114914\n");
+    // Check hex and base64 encodings of integers
+    let output = sc_drbg(&[&args[..], &["--emit", "u32"]].concat(), &seeds);
+    assert_eq!(stdout(&output), "d1645082\nb9b66f9a\ncbc64bf8\n");
+    let output = sc_drbg(
+        &[&args[..], &["--emit", "u32", "--format", "base64"]].concat(),
+        &seeds,
+    );
+    assert_eq!(stdout(&output), "0WRQgrm2b5rLxkv4\n");
+}
+
+#[test]
+fn cli_bytes() {
+    // Seed elements as a JSON file
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli_seeds.json");
+    let json = format!("[\"{}\"]", SEEDS.join("\", \""));
+    fs::write(&path, json).expect("Should write seed file");
+    let path = path.to_str().expect("Should be a UTF-8 path");
+    // Check that JSON and hex line seed files generate the same bytes
+    let args = ["--digest", "sha256", "--counter", "u32", "--subset", "3"];
+    let hex =
+        stdout(&sc_drbg(&[&["-"], &args[..]].concat(), &SEEDS.join("\n")));
+    let json = stdout(&sc_drbg(&[&[path], &args[..]].concat(), ""));
+    assert_eq!(hex, json);
+    assert_eq!(hex.trim_end().len(), 64);
+    // Check that raw output has the requested length
+    let raw = sc_drbg(
+        &[&[path], &["--format", "raw", "-n", "100"][..]].concat(),
+        "",
+    );
+    assert!(raw.status.success());
+    assert_eq!(raw.stdout.len(), 100);
+}
+
+#[test]
+fn cli_errors() {
+    // Check that invalid seed elements and arguments are rejected
+    let output = sc_drbg(&["-"], "ca33496c\nnot-hex\n");
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "sc-drbg: invalid hex seed element 2\n"
+    );
+    let output = sc_drbg(&["-"], "ca33496c\n+a+b\n");
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "sc-drbg: invalid hex seed element 2\n"
+    );
+    let output = sc_drbg(&["-"], "[\"ca33496c\", \"-f-f\"]");
+    assert!(!output.status.success());
+    let output = sc_drbg(&["-"], "");
+    assert!(!output.status.success());
+    let output = sc_drbg(&["-", "--format", "dec"], SEEDS[0]);
+    assert!(!output.status.success());
+    let output = sc_drbg(&["-", "--digest", "md5"], SEEDS[0]);
+    assert!(!output.status.success());
+}
@@ -0,0 +1,61 @@
+use std::{path::Path, process::Command};
+
+const TARGET: &str = "i686-unknown-linux-musl";
+
+fn target_installed() -> bool {
+    // Look for the standard library of the target in the sysroot
+    let output = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .expect("Should run rustc");
+    let sysroot = String::from_utf8_lossy(&output.stdout);
+    Path::new(sysroot.trim())
+        .join("lib/rustlib")
+        .join(TARGET)
+        .exists()
+}
+
+fn run_transcript(target: Option<&str>) -> String {
+    // Run the determinism example, natively or for the given target
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let mut command = Command::new(env!("CARGO"));
+    command
+        .current_dir(manifest_dir)
+        .args(["run", "--quiet", "--example", "determinism"])
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(manifest_dir).join("target/cross_target"),
+        );
+    if let Some(target) = target {
+        command.args(["--target", target]);
+    }
+    let output = command.output().expect("Should run cargo");
+    assert!(
+        output.status.success(),
+        "determinism example failed for {}: {}",
+        target.unwrap_or("host"),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("Should output UTF-8")
+}
+
+#[test]
+fn drbg_cross_target() {
+    // Skip unless the 32 bit target can run on this host and is installed
+    if !cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        eprintln!("skipping: requires an x86_64 Linux host");
+        return;
+    }
+    if !target_installed() {
+        eprintln!("skipping: rustup target add {} to run", TARGET);
+        return;
+    }
+    // Check that a 32 bit host generates the same outputs as a 64 bit host
+    let native = run_transcript(None);
+    let i686 = run_transcript(Some(TARGET));
+    assert!(!native.is_empty());
+    for (line, (a, b)) in native.lines().zip(i686.lines()).enumerate() {
+        assert_eq!(a, b, "outputs differ at line {}", line + 1);
+    }
+    assert_eq!(native.lines().count(), i686.lines().count());
+}
@@ -0,0 +1,241 @@
+#![cfg(feature = "dyn")]
+
+use hex_literal::hex;
+use rand_core::RngCore;
+use sc_drbg::{
+    Algorithm, CounterWidth, DigestAlgorithm, Drbg, DrbgError, DynDrbg, Endian,
+};
+use sha2::Sha512;
+use sha3::Sha3_256;
+
+fn get_seed_vec() -> Vec<Vec<u8>> {
+    let arr = vec![
+        hex!("ca33496c5c9e5f3ce6e932a0670d320f").to_vec(),
+        hex!("e17baaae2056f7cea2083482f9818b1c").to_vec(),
+        hex!("2c1aef2c624598ae937eed2b5ad9448b").to_vec(),
+        hex!("6932a3726327aa4a092771dabf198fc7").to_vec(),
+        hex!("fe9fe0c3b16f8ae27b09856bd0f487d1").to_vec(),
+        hex!("87c83f8f122b3bcccf42a97f487133f9").to_vec(),
+        hex!("5bc58505a5cc3406168facc39ba0f5dc").to_vec(),
+    ];
+    arr
+}
+
+fn get_reseed_vec() -> Vec<Vec<u8>> {
+    let arr = vec![
+        hex!("0f1e2d3c4b5a69788796a5b4c3d2e1f0").to_vec(),
+        hex!("00112233445566778899").to_vec(),
+    ];
+    arr
+}
+
+#[test]
+fn drbg_dynamic() {
+    // Expected u64 outputs of SHA-256, 64 bit counter, big-endian
+    let sha256_be_u64: [u64; 5] = [
+        9137872933168510779,
+        4439943573148050097,
+        1574340017750313000,
+        12348847195645694781,
+        12678918727278954955,
+    ];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using a runtime descriptor for SHA-256
+    let algorithm: Algorithm = "SHA-256/u64/BE"
+        .parse()
+        .expect("Should parse algorithm descriptor");
+    let mut drbg = DynDrbg::new(algorithm, &arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(drbg.algorithm(), algorithm);
+    // Check that each generated u64 matches expected output
+    for expected in sha256_be_u64 {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+    // Check that output matches the statically typed DRBG
+    let algorithm: Algorithm =
+        "sha-512/U128/le".parse().expect("Should parse descriptor");
+    let mut dynamic = DynDrbg::new(algorithm, &arr, Some(context), false)
+        .expect("Should create new SC_DRBG instance");
+    let mut fixed = Drbg::<Sha512, u128>::new_le(&arr, Some(context), false)
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(dynamic.next_u32_subset(3), fixed.next_u32_subset(3));
+    assert_eq!(dynamic.next_u64_subset(5), fixed.next_u64_subset(5));
+    let (mut a, mut b) = ([0u8; 48], [0u8; 48]);
+    dynamic
+        .fill_bytes_with_elements(&[6, 2], &mut a)
+        .expect("Should fill bytes with elements");
+    fixed
+        .fill_bytes_with_elements(&[6, 2], &mut b)
+        .expect("Should fill bytes with elements");
+    assert_eq!(a, b);
+    let additional = get_reseed_vec();
+    dynamic
+        .reseed(&additional, Some("epoch-1"))
+        .expect("Should reseed SC_DRBG instance");
+    fixed
+        .reseed(&additional, Some("epoch-1"))
+        .expect("Should reseed SC_DRBG instance");
+    dynamic.fill_bytes(&mut a);
+    fixed.fill_bytes(&mut b);
+    assert_eq!(a, b);
+    // Check that every supported combination can be created
+    for digest in [
+        "SHA-224", "SHA-256", "SHA-384", "SHA-512", "SHA3-224", "SHA3-256",
+        "SHA3-384", "SHA3-512",
+    ] {
+        for counter in ["u32", "u64", "u128"] {
+            let descriptor = format!("{}/{}/LE", digest, counter);
+            let algorithm: Algorithm =
+                descriptor.parse().expect("Should parse descriptor");
+            assert_eq!(algorithm.to_string(), descriptor);
+            let mut drbg = DynDrbg::new(algorithm, &arr, None, true)
+                .expect("Should create new SC_DRBG instance");
+            drbg.next_u64();
+        }
+    }
+}
+
+#[test]
+fn drbg_dynamic_errors() {
+    // Check parsing of each component of a descriptor
+    let algorithm = Algorithm::new(
+        DigestAlgorithm::Sha3_256,
+        CounterWidth::U32,
+        Endian::BigEndian,
+    );
+    assert_eq!("SHA3-256/u32/BE".parse(), Ok(algorithm));
+    assert_eq!("sha256".parse(), Ok(DigestAlgorithm::Sha256));
+    // Check that malformed descriptors are rejected
+    for descriptor in [
+        "",
+        "SHA3-256",
+        "SHA3-256/u64",
+        "SHA3-256/u64/BE/extra",
+        "MD5/u64/BE",
+        "SHA3-256/u16/BE",
+        "SHA3-256/u64/ME",
+    ] {
+        assert_eq!(
+            descriptor.parse::<Algorithm>(),
+            Err(DrbgError::InvalidAlgorithm(descriptor.to_string()))
+        );
+    }
+    // Check that seed material is validated
+    let result = DynDrbg::new(algorithm, &[], None, true);
+    assert_eq!(result.err(), Some(DrbgError::EmptyArray));
+}
+
+#[test]
+fn drbg_dynamic_input() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let inputs: [&[u8]; 3] = [b"user-1001", b"user-1002", b"user-1001"];
+    // Check that output bound to additional input matches Drbg
+    let algorithm = "SHA3-256/u32/LE".parse().expect("Should parse");
+    let mut dynamic = DynDrbg::new(algorithm, &arr, Some(context), false)
+        .expect("Should create new SC_DRBG instance");
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), false)
+        .expect("Should create new SC_DRBG instance");
+    let (mut bytes, mut dyn_bytes) = ([0u8; 48], [0u8; 48]);
+    for input in inputs {
+        drbg.fill_bytes_with_input(3, input, &mut bytes)
+            .expect("Should fill bytes");
+        dynamic
+            .fill_bytes_with_input(3, input, &mut dyn_bytes)
+            .expect("Should fill bytes");
+        assert_eq!(dyn_bytes, bytes);
+    }
+}
+
+#[test]
+fn drbg_dynamic_fork() {
+    // Expected outputs of a child, and of the parent after forking
+    let child_u64: [u64; 1] = [451995015735309227];
+    let parent_u64: [u64; 1] = [17251737185066698826];
+    let split_u64: [u64; 3] = [
+        11122013858613022500,
+        8612973042293103379,
+        5075630943971354076,
+    ];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Check that a DynDrbg forks the same children
+    let algorithm: Algorithm =
+        "SHA3-256/u64/LE".parse().expect("Should parse algorithm");
+    let new_dyn_drbg = || {
+        DynDrbg::new(algorithm, &arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance")
+    };
+    let mut dyn_drbg = new_dyn_drbg();
+    assert_eq!(dyn_drbg.fork("worker-0").next_u64(), child_u64[0]);
+    assert_eq!(dyn_drbg.next_u64(), parent_u64[0]);
+    for (i, child) in new_dyn_drbg().split_n(3).iter_mut().enumerate() {
+        assert_eq!(child.algorithm(), algorithm);
+        assert_eq!(child.next_u64(), split_u64[i]);
+    }
+}
+
+#[test]
+fn drbg_dynamic_seekable() {
+    // Expected output at counter 10000, and first output after a ratchet
+    let seek_bytes = hex!("b7e4f4ca35abdaa67af58dbc5c7e4820");
+    let ratchet_bytes = hex!(
+        "bcdaf28015e42e3dc917389db146df7d3715c99d4d799df29866f328585a39f5"
+    );
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Check that a DynDrbg seeks the same outputs
+    let algorithm: Algorithm =
+        "SHA3-256/u64/LE".parse().expect("Should parse algorithm");
+    let mut dyn_drbg =
+        DynDrbg::new_seekable(algorithm, &arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+    let mut seek = [0u8; 16];
+    dyn_drbg
+        .output_at(10000, 2, &mut seek)
+        .expect("Should seek output");
+    assert_eq!(seek, seek_bytes);
+    dyn_drbg.ratchet();
+    let mut bytes = [0u8; 32];
+    dyn_drbg.fill_bytes(&mut bytes);
+    assert_eq!(bytes, ratchet_bytes);
+}
+
+#[test]
+fn drbg_dynamic_seekable_errors() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let mut bytes = [0u8; 16];
+    // Check that seeking requires the seekable mode
+    let algorithm: Algorithm =
+        "SHA3-256/u32/LE".parse().expect("Should parse algorithm");
+    let dyn_drbg = DynDrbg::new(algorithm, &arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(
+        dyn_drbg.output_at(0, 1, &mut bytes),
+        Err(DrbgError::SeekUnavailable)
+    );
+    // Check that counter values beyond the counter width are rejected
+    let exhausted = Err(DrbgError::CounterExhausted {
+        max: u32::MAX as u128,
+    });
+    let dyn_drbg = DynDrbg::new_seekable(algorithm, &arr, None, true)
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(
+        dyn_drbg.output_at(u32::MAX as u128, 1, &mut bytes),
+        exhausted
+    );
+    assert_eq!(dyn_drbg.output_at(u128::MAX, 1, &mut bytes), exhausted);
+    assert!(
+        dyn_drbg
+            .output_at(u32::MAX as u128 - 1, 1, &mut bytes)
+            .is_ok()
+    );
+}
@@ -1,7 +1,12 @@
 use hex_literal::hex;
-use rand_core::RngCore;
-use sc_drbg::Drbg;
-use sha3::Sha3_256;
+use rand_core::{CryptoRng, RngCore, SeedableRng, TryRngCore};
+use sc_drbg::{
+    AesCtr, AuditedDrbg, ChaCha20, CommitmentMode, Drbg, DrbgError,
+    ElementProof, Endian, EvolutionMode, QuorumDrbg, SeededDrbg, Shake256Xof,
+    StaticDrbg, verify_element_proof, verify_transcript,
+};
+use sha2::{Sha224, Sha256, Sha384, Sha512, Sha512_256};
+use sha3::{Sha3_256, Sha3_512};
 
 fn get_seed_vec() -> Vec<Vec<u8>> {
     let arr = vec![
@@ -35,17 +40,17 @@ fn drbg_u32_le() {
     let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
         .expect("Should create new SC_DRBG instance");
     // Check that each generated u32 matches expected output
-    for i in 0..5 {
+    for expected in u32_le_u32 {
         let num = drbg.next_u32();
-        assert_eq!(num, u32_le_u32[i]);
+        assert_eq!(num, expected);
     }
     // Re-initialize DRBG
     let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
         .expect("Should create new SC_DRBG instance");
     // Check that each generated u64 matches expected output
-    for i in 0..5 {
+    for expected in u32_le_u64 {
         let num = drbg.next_u64();
-        assert_eq!(num, u32_le_u64[i]);
+        assert_eq!(num, expected);
     }
 }
 
@@ -68,17 +73,17 @@ fn drbg_u32_be() {
     let mut drbg = Drbg::<Sha3_256, u32>::new_be(&arr, Some(context), true)
         .expect("Should create new SC_DRBG instance");
     // Check that each generated u32 matches expected output
-    for i in 0..5 {
+    for expected in u32_be_u32 {
         let num = drbg.next_u32();
-        assert_eq!(num, u32_be_u32[i]);
+        assert_eq!(num, expected);
     }
     // Re-initialize DRBG
     let mut drbg = Drbg::<Sha3_256, u32>::new_be(&arr, Some(context), true)
         .expect("Should create new SC_DRBG instance");
     // Check that each generated u64 matches expected output
-    for i in 0..5 {
+    for expected in u32_be_u64 {
         let num = drbg.next_u64();
-        assert_eq!(num, u32_be_u64[i]);
+        assert_eq!(num, expected);
     }
 }
 
@@ -101,17 +106,17 @@ fn drbg_u64_le() {
     let mut drbg = Drbg::<Sha3_256, u64>::new_le(&arr, Some(context), true)
         .expect("Should create new SC_DRBG instance");
     // Check that each generated u32 matches expected output
-    for i in 0..5 {
+    for expected in u64_le_u32 {
         let num = drbg.next_u32();
-        assert_eq!(num, u64_le_u32[i]);
+        assert_eq!(num, expected);
     }
     // Re-initialize DRBG
     let mut drbg = Drbg::<Sha3_256, u64>::new_le(&arr, Some(context), true)
         .expect("Should create new SC_DRBG instance");
     // Check that each generated u64 matches expected output
-    for i in 0..5 {
+    for expected in u64_le_u64 {
         let num = drbg.next_u64();
-        assert_eq!(num, u64_le_u64[i]);
+        assert_eq!(num, expected);
     }
 }
 
@@ -134,16 +139,1819 @@ fn drbg_u64_be() {
     let mut drbg = Drbg::<Sha3_256, u64>::new_be(&arr, Some(context), true)
         .expect("Should create new SC_DRBG instance");
     // Check that each generated u32 matches expected output
-    for i in 0..5 {
+    for expected in u64_be_u32 {
         let num = drbg.next_u32();
-        assert_eq!(num, u64_be_u32[i]);
+        assert_eq!(num, expected);
     }
     // Re-initialize DRBG
     let mut drbg = Drbg::<Sha3_256, u64>::new_be(&arr, Some(context), true)
         .expect("Should create new SC_DRBG instance");
     // Check that each generated u64 matches expected output
-    for i in 0..5 {
+    for expected in u64_be_u64 {
         let num = drbg.next_u64();
-        assert_eq!(num, u64_be_u64[i]);
+        assert_eq!(num, expected);
     }
 }
+
+#[test]
+fn drbg_u128_le() {
+    // Expected u32 and u64 outputs
+    let u128_le_u32: [u32; 5] =
+        [1858434144, 3837292294, 3076156596, 632704735, 1929714555];
+    let u128_le_u64: [u64; 5] = [
+        15477751986933560416,
+        16421257480431617124,
+        11184462510157860662,
+        5298597395727538427,
+        5383776133835333585,
+    ];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 128 bit counter, little-endian
+    let mut drbg = Drbg::<Sha3_256, u128>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Check that each generated u32 matches expected output
+    for expected in u128_le_u32 {
+        let num = drbg.next_u32();
+        assert_eq!(num, expected);
+    }
+    // Re-initialize DRBG
+    let mut drbg = Drbg::<Sha3_256, u128>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Check that each generated u64 matches expected output
+    for expected in u128_le_u64 {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+}
+
+#[test]
+fn drbg_u128_be() {
+    // Expected u32 and u64 outputs
+    let u128_be_u32: [u32; 5] =
+        [3514482434, 1799571752, 498757277, 3816952236, 2403573296];
+    let u128_be_u64: [u64; 5] = [
+        15094587117925903254,
+        15160900203951463552,
+        6901898145957442825,
+        7598392415963633194,
+        6241341032611472739,
+    ];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 128 bit counter, big-endian
+    let mut drbg = Drbg::<Sha3_256, u128>::new_be(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Check that each generated u32 matches expected output
+    for expected in u128_be_u32 {
+        let num = drbg.next_u32();
+        assert_eq!(num, expected);
+    }
+    // Re-initialize DRBG
+    let mut drbg = Drbg::<Sha3_256, u128>::new_be(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Check that each generated u64 matches expected output
+    for expected in u128_be_u64 {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+}
+
+#[test]
+fn drbg_builder_defaults() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBGs from the constructors and the builder defaults
+    let mut drbg_le = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut builder_le = Drbg::<Sha3_256, u32>::builder()
+        .array(&arr)
+        .context(Some(context))
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    let mut drbg_be = Drbg::<Sha3_256, u64>::new_be(&arr, Some(context), false)
+        .expect("Should create new SC_DRBG instance");
+    let mut builder_be = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr)
+        .context(Some(context))
+        .endian(Endian::BigEndian)
+        .init(false)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    // Check that the builder matches the constructors
+    for _ in 0..5 {
+        assert_eq!(drbg_le.next_u64(), builder_le.next_u64());
+        assert_eq!(drbg_be.next_u64(), builder_be.next_u64());
+    }
+}
+
+#[test]
+fn drbg_builder_options() {
+    // Expected u32 and u64 outputs
+    let le_u32: [u32; 5] =
+        [1138811041, 985511136, 777309467, 4113689169, 4254361063];
+    let be_u64: [u64; 5] = [
+        327642879264449138,
+        11771685137447894915,
+        11221752331201958360,
+        15852990535243826778,
+        16000485352562873680,
+    ];
+    // Get seed elements from hex strings, set context and nonce
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let nonce = b"tenant-0042";
+    // Create DRBG using SHA3-256, 32 bit counter, little-endian, 3 rounds
+    let mut drbg = Drbg::<Sha3_256, u32>::builder()
+        .array(&arr)
+        .context(Some(context))
+        .nonce(nonce)
+        .rounds(3)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    // Check that each generated u32 matches expected output
+    for expected in le_u32 {
+        let num = drbg.next_u32();
+        assert_eq!(num, expected);
+    }
+    // Create DRBG using SHA3-256, 64 bit counter, big-endian, 3 rounds
+    let mut drbg = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr)
+        .context(Some(context))
+        .nonce(nonce)
+        .rounds(3)
+        .endian(Endian::BigEndian)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    // Check that each generated u64 matches expected output
+    for expected in be_u64 {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+}
+
+#[test]
+fn drbg_builder_errors() {
+    // Missing array of seed material
+    let result = Drbg::<Sha3_256, u32>::builder().build();
+    assert_eq!(result.err(), Some(DrbgError::EmptyArray));
+    // Array with empty elements
+    let mut arr = get_seed_vec();
+    arr[2].clear();
+    arr[5].clear();
+    let result = Drbg::<Sha3_256, u32>::builder().array(&arr).build();
+    assert_eq!(result.err(), Some(DrbgError::EmptyElement(vec![2, 5])));
+    // Zero mixing rounds, and an empty nonce
+    let arr = get_seed_vec();
+    let result = Drbg::<Sha3_256, u32>::builder()
+        .array(&arr)
+        .rounds(0)
+        .build();
+    assert_eq!(result.err(), Some(DrbgError::InvalidRounds));
+    let result = Drbg::<Sha3_256, u32>::builder()
+        .array(&arr)
+        .nonce(b"")
+        .build();
+    assert_eq!(result.err(), Some(DrbgError::EmptyNonce));
+}
+
+fn get_reseed_vec() -> Vec<Vec<u8>> {
+    let arr = vec![
+        hex!("0f1e2d3c4b5a69788796a5b4c3d2e1f0").to_vec(),
+        hex!("00112233445566778899").to_vec(),
+    ];
+    arr
+}
+
+#[test]
+fn drbg_reseed() {
+    // Expected u32 and u64 outputs after reseeding
+    let le_u32: [u32; 3] = [1819938131, 206646528, 1743849226];
+    let be_u64: [u64; 3] = [
+        3125721803608144143,
+        5782681216392407556,
+        2718270584297331639,
+    ];
+    // Get seed elements and additional elements, set context
+    let arr = get_seed_vec();
+    let additional = get_reseed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 32 bit counter, little-endian
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Advance the state, then reseed with a label
+    drbg.next_u32();
+    drbg.next_u32();
+    drbg.reseed(&additional, Some("reseed-1"))
+        .expect("Should reseed SC_DRBG instance");
+    // Check that each generated u32 matches expected output
+    for expected in le_u32 {
+        let num = drbg.next_u32();
+        assert_eq!(num, expected);
+    }
+    // Create DRBG using SHA3-256, 64 bit counter, big-endian
+    let mut drbg = Drbg::<Sha3_256, u64>::new_be(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Advance the state, then reseed without a label
+    drbg.next_u64();
+    drbg.next_u64();
+    drbg.reseed(&additional, None)
+        .expect("Should reseed SC_DRBG instance");
+    // Check that each generated u64 matches expected output
+    for expected in be_u64 {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+    // Check that element boundaries and the label are bound into a reseed
+    let reseeded = |context, additional: &[Vec<u8>], label| {
+        let mut drbg = Drbg::<Sha3_256, u64>::new_le(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+        drbg.reseed(additional, label)
+            .expect("Should reseed SC_DRBG instance");
+        drbg.next_u64()
+    };
+    let split = reseeded(context, &[vec![0xAB], vec![0xCD]], None);
+    let joined = reseeded(context, &[vec![0xAB, 0xCD]], None);
+    assert_ne!(split, joined);
+    assert_ne!(
+        reseeded("a-RESEED-", &additional, Some("")),
+        reseeded("a", &additional, Some("-RESEED-"))
+    );
+}
+
+#[test]
+fn drbg_reseed_errors() {
+    // Get seed elements, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Empty array of additional seed material
+    let result = drbg.reseed(&[], None);
+    assert_eq!(result, Err(DrbgError::EmptyArray));
+    // Additional seed material with an empty element
+    let additional = vec![vec![0xAA; 16], vec![]];
+    let result = drbg.reseed(&additional, None);
+    assert_eq!(result, Err(DrbgError::EmptyElement(vec![1])));
+}
+
+#[test]
+fn drbg_fallible() {
+    // Expected u32 and u64 outputs
+    let u32_le_u32: [u32; 5] =
+        [2296859039, 3520090129, 755322988, 1089056308, 1233950592];
+    let u64_be_u64: [u64; 5] = [
+        2158770911501693864,
+        57669768752051356,
+        14834690014904699227,
+        1061605113615837153,
+        17929217830921720000,
+    ];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 32 bit counter, little-endian
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Check that each fallible u32 matches expected output
+    for expected in u32_le_u32 {
+        let num = drbg.try_next_u32_subset(arr.len());
+        assert_eq!(num, Ok(expected));
+    }
+    // Create DRBG using SHA3-256, 64 bit counter, big-endian
+    let mut drbg = Drbg::<Sha3_256, u64>::new_be(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Check that each u64 from the TryRngCore adapter matches expected output
+    let mut rng = drbg.try_rng();
+    for expected in u64_be_u64 {
+        let num = rng.try_next_u64();
+        assert_eq!(num, Ok(expected));
+    }
+}
+
+#[test]
+fn drbg_seedable() {
+    // Expected u32 and u64 outputs
+    let seed_u32: [u32; 5] =
+        [903796173, 1348667308, 1897661816, 1007144457, 313270537];
+    let state_u64: [u64; 5] = [
+        10608448125528318429,
+        2330784372717817872,
+        11272687929320955526,
+        12965551204087563716,
+        749620376096040537,
+    ];
+    // Create DRBG from a 32 byte seed
+    let seed: [u8; 32] = hex!(
+        "ca33496c5c9e5f3ce6e932a0670d320fe17baaae2056f7cea2083482f9818b1c"
+    );
+    let mut drbg = Drbg::<Sha3_256, u32>::from_seed(seed);
+    // Check that each generated u32 matches expected output
+    for expected in seed_u32 {
+        let num = drbg.next_u32();
+        assert_eq!(num, expected);
+    }
+    // Check that the seed layout matches the builder
+    let mut drbg = Drbg::<Sha3_256, u32>::from_seed(seed);
+    let mut builder = Drbg::<Sha3_256, u32>::builder()
+        .seed(&seed, Drbg::<Sha3_256, u32>::SEED_ELEMENTS)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(drbg.next_u64(), builder.next_u64());
+    // Check that a seeded generator with the default layout matches, and
+    // that other layouts match the builder
+    let mut drbg = Drbg::<Sha3_256, u32>::from_seed(seed);
+    let mut seeded = SeededDrbg::<Sha3_256, u32, 4>::from_seed(seed);
+    assert_eq!(seeded.next_u64(), drbg.next_u64());
+    let mut seeded = SeededDrbg::<Sha3_256, u32, 8>::from_seed(seed);
+    assert_eq!(seeded.drbg().element_count(), 8);
+    let mut builder = Drbg::<Sha3_256, u32>::builder()
+        .seed(&seed, 8)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(seeded.next_u64(), builder.next_u64());
+    let mut single = SeededDrbg::<Sha3_256, u32, 1>::from_seed(seed);
+    assert_ne!(
+        single.next_u64(),
+        Drbg::<Sha3_256, u32>::from_seed(seed).next_u64()
+    );
+    let mut first = SeededDrbg::<Sha3_256, u64, 2>::seed_from_u64(7);
+    let mut second = SeededDrbg::<Sha3_256, u64, 2>::seed_from_u64(7);
+    assert_eq!(first.next_u64(), second.next_u64());
+    assert_eq!(
+        first.into_inner().next_u64(),
+        second.into_inner().next_u64()
+    );
+    // Create DRBG from a u64 state
+    let mut drbg = Drbg::<Sha3_256, u64>::seed_from_u64(0x5344524247);
+    // Check that each generated u64 matches expected output
+    for expected in state_u64 {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+}
+
+#[test]
+fn drbg_seed_layout() {
+    // Seed that cannot be split into equal elements
+    let result = Drbg::<Sha3_256, u32>::builder()
+        .seed(&[0xAA; 30], 4)
+        .build();
+    assert_eq!(
+        result.err(),
+        Some(DrbgError::InvalidSeedLayout {
+            len: 30,
+            elements: 4
+        })
+    );
+    // Seed split into zero elements
+    let result = Drbg::<Sha3_256, u32>::builder()
+        .seed(&[0xAA; 32], 0)
+        .build();
+    assert_eq!(
+        result.err(),
+        Some(DrbgError::InvalidSeedLayout {
+            len: 32,
+            elements: 0
+        })
+    );
+}
+
+#[test]
+fn drbg_crypto_rng() {
+    fn gen_key<R: CryptoRng>(rng: &mut R) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        rng.fill_bytes(&mut key);
+        key
+    }
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Check that DRBG can be used where a CryptoRng is expected
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let key = gen_key(&mut drbg);
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut bytes = [0u8; 32];
+    drbg.fill_bytes(&mut bytes);
+    assert_eq!(key, bytes);
+}
+
+// Offset of the counter in a state snapshot, using the AES-CTR backend
+const STATE_CTR_OFFSET: usize = 32;
+
+#[test]
+fn drbg_state_roundtrip() {
+    // Expected u32 outputs
+    let u32_le_u32: [u32; 5] =
+        [2296859039, 3520090129, 755322988, 1089056308, 1233950592];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 32 bit counter, little-endian
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Advance the state, then export it
+    for expected in &u32_le_u32[0..2] {
+        assert_eq!(drbg.next_u32(), *expected);
+    }
+    let state = drbg.export_state();
+    drop(drbg);
+    // Restore DRBG from the exported state
+    let mut drbg = Drbg::<Sha3_256, u32>::import_state(&state)
+        .expect("Should import SC_DRBG state");
+    // Check that the restored DRBG continues the expected outputs
+    for expected in &u32_le_u32[2..5] {
+        assert_eq!(drbg.next_u32(), *expected);
+    }
+    // Check that exporting again produces the same snapshot format
+    let mut restored =
+        Drbg::<Sha3_256, u32>::import_state(&drbg.export_state())
+            .expect("Should import SC_DRBG state");
+    assert_eq!(drbg.next_u64(), restored.next_u64());
+}
+
+#[test]
+fn drbg_state_errors() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let drbg = Drbg::<Sha3_256, u32>::new_be(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let state = drbg.export_state();
+    // Mismatched counter width
+    let result = Drbg::<Sha3_256, u64>::import_state(&state);
+    assert_eq!(
+        result.err(),
+        Some(DrbgError::CounterMismatch {
+            expected: 8,
+            found: 4
+        })
+    );
+    // Mismatched digest
+    let result = Drbg::<Sha3_512, u32>::import_state(&state);
+    assert_eq!(result.err(), Some(DrbgError::DigestMismatch));
+    // Unsupported version
+    let mut modified = state.to_vec();
+    modified[6] = 0xFF;
+    let result = Drbg::<Sha3_256, u32>::import_state(&modified);
+    assert_eq!(result.err(), Some(DrbgError::UnsupportedStateVersion(0xFF)));
+    // Truncated and extended snapshots
+    let result = Drbg::<Sha3_256, u32>::import_state(&state[..state.len() - 1]);
+    assert_eq!(result.err(), Some(DrbgError::InvalidState));
+    let mut modified = state.to_vec();
+    modified.push(0x00);
+    let result = Drbg::<Sha3_256, u32>::import_state(&modified);
+    assert_eq!(result.err(), Some(DrbgError::InvalidState));
+}
+
+#[test]
+fn drbg_counter_exhausted() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Restore DRBG with the counter at its maximum value
+    let drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut state = drbg.export_state().to_vec();
+    state[STATE_CTR_OFFSET..STATE_CTR_OFFSET + 4].fill(0xFF);
+    let mut drbg = Drbg::<Sha3_256, u32>::import_state(&state)
+        .expect("Should import SC_DRBG state");
+    // Check that fallible methods return an error
+    let exhausted = DrbgError::CounterExhausted {
+        max: u32::MAX as u128,
+    };
+    assert_eq!(drbg.try_next_u32_subset(2), Err(exhausted.clone()));
+    assert_eq!(drbg.try_rng().try_next_u64(), Err(exhausted.clone()));
+    let mut bytes = [0u8; 16];
+    assert_eq!(drbg.try_fill_bytes_subset(7, &mut bytes), Err(exhausted));
+    assert_eq!(bytes, [0u8; 16]);
+}
+
+#[test]
+#[should_panic(expected = "Counter exhausted")]
+fn drbg_counter_exhausted_panics() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Restore DRBG with the counter at its maximum value
+    let drbg = Drbg::<Sha3_256, u64>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut state = drbg.export_state().to_vec();
+    state[STATE_CTR_OFFSET..STATE_CTR_OFFSET + 8].fill(0xFF);
+    let mut drbg = Drbg::<Sha3_256, u64>::import_state(&state)
+        .expect("Should import SC_DRBG state");
+    // Check that infallible methods panic
+    drbg.next_u64();
+}
+
+#[test]
+fn drbg_counter_exhausted_u128() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Restore DRBG with the counter at its maximum value
+    let drbg = Drbg::<Sha3_256, u128>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut state = drbg.export_state().to_vec();
+    state[STATE_CTR_OFFSET..STATE_CTR_OFFSET + 16].fill(0xFF);
+    let mut drbg = Drbg::<Sha3_256, u128>::import_state(&state)
+        .expect("Should import SC_DRBG state");
+    // Check that the full counter width is compared against the maximum
+    let exhausted = DrbgError::CounterExhausted { max: u128::MAX };
+    assert_eq!(drbg.try_next_u64_subset(2), Err(exhausted));
+    // Check that a counter past the u64 range still generates output
+    state[STATE_CTR_OFFSET..STATE_CTR_OFFSET + 8].fill(0x00);
+    let mut drbg = Drbg::<Sha3_256, u128>::import_state(&state)
+        .expect("Should import SC_DRBG state");
+    assert!(drbg.try_next_u64_subset(2).is_ok());
+    // Check that the counter width must match on import
+    assert_eq!(
+        Drbg::<Sha3_256, u64>::import_state(&state).err(),
+        Some(DrbgError::CounterMismatch {
+            expected: 8,
+            found: 16
+        })
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn drbg_state_serde() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 64 bit counter, big-endian
+    let mut drbg = Drbg::<Sha3_256, u64>::new_be(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    drbg.next_u64();
+    // Serialize and deserialize the DRBG
+    let json = serde_json::to_string(&drbg).expect("Should serialize");
+    let mut restored: Drbg<Sha3_256, u64> =
+        serde_json::from_str(&json).expect("Should deserialize");
+    // Check that the restored DRBG continues the same outputs
+    for _ in 0..3 {
+        assert_eq!(drbg.next_u64(), restored.next_u64());
+    }
+    // Check that a mismatched DRBG is refused
+    let result = serde_json::from_str::<Drbg<Sha3_256, u32>>(&json);
+    assert!(result.is_err());
+}
+
+#[test]
+fn drbg_with_elements() {
+    // Expected outputs for each set of selected elements
+    let selections: [(&[usize], [u8; 16]); 3] = [
+        (&[0, 3, 5], hex!("7b7a6b49e65ead880afc0ec29087ff8d")),
+        (&[6], hex!("7efdae44daa0240c146c1d9a7118241e")),
+        (&[1, 2, 4], hex!("6314219c45e2828d19cfe49266adc3ca")),
+    ];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 32 bit counter, little-endian
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    // Check that each output matches expected output
+    for (indices, expected) in selections {
+        let mut bytes = [0u8; 16];
+        drbg.fill_bytes_with_elements(indices, &mut bytes)
+            .expect("Should fill bytes with elements");
+        assert_eq!(bytes, expected);
+    }
+    // Check that the order of indices does not affect output
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut bytes = [0u8; 16];
+    drbg.fill_bytes_with_elements(&[5, 0, 3], &mut bytes)
+        .expect("Should fill bytes with elements");
+    assert_eq!(bytes, selections[0].1);
+    // Check that selecting all elements matches the RngCore output
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut all = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    for _ in 0..3 {
+        let mut bytes = [0u8; 16];
+        drbg.fill_bytes_with_elements(&[6, 5, 4, 3, 2, 1, 0], &mut bytes)
+            .expect("Should fill bytes with elements");
+        let mut expected = [0u8; 16];
+        all.fill_bytes(&mut expected);
+        assert_eq!(bytes, expected);
+    }
+}
+
+#[test]
+fn drbg_with_elements_errors() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut bytes = [0u8; 16];
+    // Out of range indices
+    let result = drbg.fill_bytes_with_elements(&[0, 7, 9], &mut bytes);
+    assert_eq!(result, Err(DrbgError::IndexOutOfRange(vec![7, 9])));
+    // Duplicate indices
+    let result = drbg.fill_bytes_with_elements(&[1, 4, 1, 4, 1], &mut bytes);
+    assert_eq!(result, Err(DrbgError::DuplicateIndex(vec![1, 4])));
+    // Empty selection
+    let result = drbg.fill_bytes_with_elements(&[], &mut bytes);
+    assert_eq!(result, Err(DrbgError::EmptySelection));
+    // Check that the state is unchanged after errors
+    assert_eq!(bytes, [0u8; 16]);
+    assert_eq!(drbg.next_u32(), 2296859039);
+}
+
+#[test]
+fn drbg_with_input() {
+    // Expected outputs bound to additional input
+    let expected: [[u8; 16]; 3] = [
+        hex!("182776f673dd66a13af9d7f72bb727b0"),
+        hex!("db93b85afbb4831a1d799c47fb4325b9"),
+        hex!("f587da2f516a2752a8d79007ea75a821"),
+    ];
+    let inputs: [&[u8]; 3] = [b"user-1001", b"user-1002", b"user-1001"];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 32 bit counter, little-endian
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    for (input, expected) in inputs.iter().zip(&expected) {
+        let mut bytes = [0u8; 16];
+        drbg.fill_bytes_with_input(4, input, &mut bytes)
+            .expect("Should fill bytes");
+        assert_eq!(bytes, *expected);
+    }
+    // Check that outputs differ from those without additional input
+    let mut plain = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut bytes = [0u8; 16];
+    plain.fill_bytes_subset(4, &mut bytes);
+    assert_ne!(bytes, expected[0]);
+    // Check that empty input matches output and state without input
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut plain = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let (mut bytes, mut plain_bytes) = ([0u8; 48], [0u8; 48]);
+    for subset in 1..=8 {
+        drbg.fill_bytes_with_input(subset, &[], &mut bytes)
+            .expect("Should fill bytes");
+        plain.fill_bytes_subset(subset, &mut plain_bytes);
+        assert_eq!(bytes, plain_bytes);
+    }
+    assert_eq!(drbg.next_u64(), plain.next_u64());
+    // Check that additional input is bound into the state update
+    let new_drbg = || {
+        Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance")
+    };
+    let (mut drbg, mut other, mut plain) = (new_drbg(), new_drbg(), new_drbg());
+    drbg.fill_bytes_with_input(4, b"user-1001", &mut bytes)
+        .expect("Should fill bytes");
+    other
+        .fill_bytes_with_input(4, b"user-1001", &mut bytes)
+        .expect("Should fill bytes");
+    plain.fill_bytes_subset(4, &mut bytes);
+    assert_eq!(drbg.next_u64(), other.next_u64());
+    assert_ne!(drbg.next_u64(), plain.next_u64());
+    // Check that StaticDrbg matches Drbg
+    let arr_fixed: [[u8; 16]; 7] =
+        core::array::from_fn(|i| arr[i].clone().try_into().unwrap());
+    let mut fixed = StaticDrbg::<Sha3_256, u32, 7, 16>::new_le(
+        &arr_fixed,
+        Some(context),
+        false,
+    )
+    .expect("Should create new SC_DRBG instance");
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), false)
+        .expect("Should create new SC_DRBG instance");
+    let mut fixed_bytes = [0u8; 48];
+    for input in inputs {
+        drbg.fill_bytes_with_input(3, input, &mut bytes)
+            .expect("Should fill bytes");
+        fixed
+            .fill_bytes_with_input(3, input, &mut fixed_bytes)
+            .expect("Should fill bytes");
+        assert_eq!(fixed_bytes, bytes);
+    }
+}
+
+#[test]
+fn drbg_chacha20() {
+    // Expected u32 and u64 outputs
+    let le_u32: [u32; 5] =
+        [1982688109, 501880357, 3174096147, 458223861, 2620717031];
+    let be_u64: [u64; 5] = [
+        15057224900698491910,
+        4121180175883523340,
+        1453397160055592993,
+        15266371329823010361,
+        1970321824091842854,
+    ];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 32 bit counter, little-endian, ChaCha20
+    let mut drbg =
+        Drbg::<Sha3_256, u32, ChaCha20>::new_le(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+    // Check that each generated u32 matches expected output
+    for expected in le_u32 {
+        let num = drbg.next_u32();
+        assert_eq!(num, expected);
+    }
+    // Create DRBG using SHA3-256, 64 bit counter, big-endian, ChaCha20
+    let mut drbg =
+        Drbg::<Sha3_256, u64, ChaCha20>::new_be(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+    // Check that each generated u64 matches expected output
+    for expected in be_u64 {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+    // Check that a state snapshot is refused by a different backend
+    let state = drbg.export_state();
+    let result = Drbg::<Sha3_256, u64, AesCtr>::import_state(&state);
+    assert_eq!(result.err(), Some(DrbgError::BackendMismatch));
+    // Check output with a digest shorter than the key, which is expanded
+    // under a label for the backend
+    let expanded_bytes = hex!(
+        "a90084e0a30967df61ac407cd3abdd425d32c5d93b203db944da69825d62efcb"
+    );
+    let mut drbg =
+        Drbg::<Sha224, u64, ChaCha20>::new_le(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+    let mut bytes = [0u8; 32];
+    drbg.fill_bytes(&mut bytes);
+    assert_eq!(bytes, expanded_bytes);
+}
+
+#[test]
+fn drbg_xof() {
+    // Expected u32 and u64 outputs
+    let le_u32: [u32; 5] =
+        [3664801011, 2959156721, 2975470269, 1026744318, 1425686529];
+    let be_u64: [u64; 5] = [
+        12236775271861672348,
+        5179569273340168710,
+        13426731252976639494,
+        18426868503879059465,
+        4544519848730116505,
+    ];
+    let be_u64_no_context: [u64; 2] = [12732315502022425829, 37447106574678431];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 32 bit counter, little-endian, cSHAKE256
+    let mut drbg =
+        Drbg::<Sha3_256, u32, Shake256Xof>::new_le(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+    // Check that each generated u32 matches expected output
+    for expected in le_u32 {
+        let num = drbg.next_u32();
+        assert_eq!(num, expected);
+    }
+    // Create DRBG using SHA3-256, 64 bit counter, big-endian, cSHAKE256
+    let mut drbg =
+        Drbg::<Sha3_256, u64, Shake256Xof>::new_be(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+    // Check that each generated u64 matches expected output
+    for expected in be_u64 {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+    // Create DRBG without a context string
+    let mut drbg = Drbg::<Sha3_256, u64, Shake256Xof>::new_be(&arr, None, true)
+        .expect("Should create new SC_DRBG instance");
+    // Check that each generated u64 matches expected output
+    for expected in be_u64_no_context {
+        let num = drbg.next_u64();
+        assert_eq!(num, expected);
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn drbg_stream() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Generate reference output with repeated calls, one per segment
+    let mut drbg = Drbg::<Sha3_256, u64>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut expected = vec![0u8; 64 * 5];
+    for segment in expected.chunks_mut(64) {
+        drbg.fill_bytes(segment);
+    }
+    // Check that reads split across segment boundaries match the reference
+    let mut stream_drbg =
+        Drbg::<Sha3_256, u64>::new_le(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+    let mut stream = stream_drbg.stream(64);
+    assert_eq!(stream.segment_len(), 64);
+    let mut out = vec![0u8; 64 * 5];
+    let mut filled = 0;
+    for len in [1, 10, 53, 130, 100, 26] {
+        let n =
+            std::io::Read::read(&mut stream, &mut out[filled..filled + len])
+                .expect("Should read from SC_DRBG stream");
+        assert_eq!(n, len);
+        filled += n;
+    }
+    assert_eq!(out, expected);
+    // Check that the state matches after whole segments
+    assert_eq!(*stream_drbg.export_state(), *drbg.export_state());
+    // Check that chunks resume from a partially read segment
+    let mut stream = stream_drbg.stream(64);
+    let mut head = [0u8; 24];
+    stream
+        .try_fill(&mut head)
+        .expect("Should fill from SC_DRBG stream");
+    let chunks: Vec<Vec<u8>> = stream.take(2).collect();
+    assert_eq!(chunks[0].len(), 40);
+    assert_eq!(chunks[1].len(), 64);
+    let mut expected = [0u8; 128];
+    for segment in expected.chunks_mut(64) {
+        drbg.fill_bytes(segment);
+    }
+    assert_eq!(head, expected[..24]);
+    assert_eq!(chunks.concat(), expected[24..]);
+    // Check that the segment length is clamped
+    assert_eq!(drbg.stream(0).segment_len(), 1);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn drbg_stream_exhausted() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Restore DRBG with one output left before the counter is exhausted
+    let drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut state = drbg.export_state().to_vec();
+    state[STATE_CTR_OFFSET..STATE_CTR_OFFSET + 4].fill(0xFF);
+    state[STATE_CTR_OFFSET + 3] = 0xFE;
+    let mut drbg = Drbg::<Sha3_256, u32>::import_state(&state)
+        .expect("Should import SC_DRBG state");
+    // Check that a read returns the bytes generated before exhaustion
+    let mut stream = drbg.stream(64);
+    let mut out = [0u8; 100];
+    let n = std::io::Read::read(&mut stream, &mut out)
+        .expect("Should read from SC_DRBG stream");
+    assert_eq!(n, 64);
+    // Check that further reads fail, and iteration ends
+    let err = std::io::Read::read(&mut stream, &mut out)
+        .expect_err("Should fail when the counter is exhausted");
+    assert_eq!(
+        err.into_inner()
+            .and_then(|e| e.downcast::<DrbgError>().ok()),
+        Some(Box::new(DrbgError::CounterExhausted {
+            max: u32::MAX as u128,
+        }))
+    );
+    assert_eq!(stream.next(), None);
+}
+
+#[test]
+fn drbg_static() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let arr_fixed: [[u8; 16]; 7] =
+        core::array::from_fn(|i| arr[i].clone().try_into().unwrap());
+    // Check that uninitialized seed material matches Drbg
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), false)
+        .expect("Should create new SC_DRBG instance");
+    let mut fixed = StaticDrbg::<Sha3_256, u32, 7, 16>::new_le(
+        &arr_fixed,
+        Some(context),
+        false,
+    )
+    .expect("Should create new SC_DRBG instance");
+    for subset in 1..=8 {
+        assert_eq!(fixed.next_u32_subset(subset), drbg.next_u32_subset(subset));
+    }
+    assert_eq!(fixed.next_u64(), drbg.next_u64());
+    // Check that initialized seed material matches Drbg, with digest length
+    // elements
+    let arr_init: [[u8; 64]; 5] = core::array::from_fn(|i| [i as u8 + 1; 64]);
+    let arr_vec: Vec<Vec<u8>> = arr_init.iter().map(|e| e.to_vec()).collect();
+    let mut drbg =
+        Drbg::<Sha3_512, u64, ChaCha20>::new_be(&arr_vec, None, true)
+            .expect("Should create new SC_DRBG instance");
+    let mut fixed = StaticDrbg::<Sha3_512, u64, 5, 64, ChaCha20>::new_be(
+        &arr_init, None, true,
+    )
+    .expect("Should create new SC_DRBG instance");
+    let mut expected = [0u8; 100];
+    let mut bytes = [0u8; 100];
+    drbg.fill_bytes_subset(3, &mut expected);
+    fixed.fill_bytes_subset(3, &mut bytes);
+    assert_eq!(bytes, expected);
+    drbg.fill_bytes_with_elements(&[4, 0], &mut expected)
+        .expect("Should fill bytes with elements");
+    fixed
+        .fill_bytes_with_elements(&[4, 0], &mut bytes)
+        .expect("Should fill bytes with elements");
+    assert_eq!(bytes, expected);
+    // Check that reseeding matches Drbg
+    let reseed = get_reseed_vec();
+    let reseed_slices: Vec<&[u8]> = reseed.iter().map(|e| &e[..]).collect();
+    drbg.reseed(&reseed, Some("epoch-1"))
+        .expect("Should reseed SC_DRBG instance");
+    fixed
+        .reseed(&reseed_slices, Some("epoch-1"))
+        .expect("Should reseed SC_DRBG instance");
+    assert_eq!(fixed.next_u64_subset(2), drbg.next_u64_subset(2));
+    // Check that the XOF backend matches Drbg
+    let mut drbg =
+        Drbg::<Sha3_256, u32, Shake256Xof>::new_le(&arr, Some(context), false)
+            .expect("Should create new SC_DRBG instance");
+    let mut fixed = StaticDrbg::<Sha3_256, u32, 7, 16, Shake256Xof>::new_le(
+        &arr_fixed,
+        Some(context),
+        false,
+    )
+    .expect("Should create new SC_DRBG instance");
+    assert_eq!(fixed.next_u64(), drbg.next_u64());
+}
+
+#[test]
+fn drbg_static_errors() {
+    // Check that elements must be the digest length when initialized
+    let arr = [[1u8; 16]; 4];
+    assert_eq!(
+        StaticDrbg::<Sha3_256, u32, 4, 16>::new_le(&arr, None, true).err(),
+        Some(DrbgError::InvalidElementLength {
+            expected: 32,
+            found: 16,
+        })
+    );
+    // Check that empty arrays and elements are rejected
+    assert_eq!(
+        StaticDrbg::<Sha3_256, u32, 0, 16>::new_le(&[], None, false).err(),
+        Some(DrbgError::EmptyArray)
+    );
+    assert_eq!(
+        StaticDrbg::<Sha3_256, u32, 2, 0>::new_le(&[[], []], None, false).err(),
+        Some(DrbgError::EmptyElement(vec![0, 1]))
+    );
+    // Check that long contexts are rejected
+    let context = "x".repeat(129);
+    assert_eq!(
+        StaticDrbg::<Sha3_256, u32, 4, 16>::new_le(&arr, Some(&context), false)
+            .err(),
+        Some(DrbgError::ContextTooLong { len: 129, max: 128 })
+    );
+    // Check that invalid indices are rejected
+    let mut drbg =
+        StaticDrbg::<Sha3_256, u32, 4, 16>::new_le(&arr, None, false)
+            .expect("Should create new SC_DRBG instance");
+    let mut bytes = [0u8; 8];
+    assert_eq!(
+        drbg.fill_bytes_with_elements(&[1, 4], &mut bytes),
+        Err(DrbgError::IndexOutOfRange(vec![4]))
+    );
+    assert_eq!(
+        drbg.fill_bytes_with_elements(&[2, 2], &mut bytes),
+        Err(DrbgError::DuplicateIndex(vec![2]))
+    );
+    assert_eq!(
+        drbg.fill_bytes_with_elements(&[], &mut bytes),
+        Err(DrbgError::EmptySelection)
+    );
+}
+
+#[test]
+fn drbg_merkle() {
+    // Expected Merkle root and u32 outputs
+    let root = hex!(
+        "4a6f581571c83a7399f54c8e8cf394d87d4e82953fa1eb98cafb01d21c3fd808"
+    );
+    let u32_merkle: [u32; 5] =
+        [3397173897, 3822509259, 990026200, 4175273974, 4027816797];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Create DRBG using SHA3-256, 64 bit counter, big-endian, committing to
+    // the uninitialized seed elements with a Merkle tree
+    let builder = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr)
+        .context(Some(context))
+        .endian(Endian::BigEndian)
+        .commitment(CommitmentMode::Merkle)
+        .init(false);
+    let mut drbg = builder.build().expect("Should create new SC_DRBG instance");
+    assert_eq!(drbg.commitment_mode(), CommitmentMode::Merkle);
+    assert_eq!(drbg.commitment_root(), root);
+    // Check that every element is proven against the root
+    for (i, element) in arr.iter().enumerate() {
+        let proof = drbg.prove_element(i).expect("Should prove element");
+        assert_eq!((proof.index, proof.count), (i, arr.len()));
+        assert!(verify_element_proof::<Sha3_256, u64>(
+            &root, element, &proof
+        ));
+        // Check that a different element, index, or root is rejected
+        let other = &arr[(i + 1) % arr.len()];
+        assert!(!verify_element_proof::<Sha3_256, u64>(&root, other, &proof));
+        let mut moved = proof.clone();
+        moved.index = (i + 1) % arr.len();
+        assert!(!verify_element_proof::<Sha3_256, u64>(
+            &root, element, &moved
+        ));
+        assert!(!verify_element_proof::<Sha3_256, u32>(
+            &root, element, &proof
+        ));
+        // Check that the blinding of another leaf, or an altered or
+        // truncated blinding, is rejected
+        let mut reblinded = proof.clone();
+        reblinded.blinding = drbg
+            .prove_element((i + 1) % arr.len())
+            .expect("Should prove element")
+            .blinding;
+        assert_ne!(reblinded.blinding, proof.blinding);
+        assert!(!verify_element_proof::<Sha3_256, u64>(
+            &root, element, &reblinded
+        ));
+        reblinded.blinding = proof.blinding.clone();
+        reblinded.blinding[0] ^= 0x01;
+        assert!(!verify_element_proof::<Sha3_256, u64>(
+            &root, element, &reblinded
+        ));
+        reblinded.blinding = proof.blinding[1..].to_vec();
+        assert!(!verify_element_proof::<Sha3_256, u64>(
+            &root, element, &reblinded
+        ));
+        let mut tampered = proof.clone();
+        tampered.siblings[0][0] ^= 0x01;
+        assert!(!verify_element_proof::<Sha3_256, u64>(
+            &root, element, &tampered
+        ));
+        tampered.siblings.pop();
+        assert!(!verify_element_proof::<Sha3_256, u64>(
+            &root, element, &tampered
+        ));
+    }
+    // Check the outputs, and that the commitment changes with the state
+    for expected in &u32_merkle[0..2] {
+        assert_eq!(drbg.next_u32(), *expected);
+    }
+    assert_ne!(drbg.commitment_root(), root);
+    // Check that the commitment mode is restored from a state snapshot
+    let mut restored =
+        Drbg::<Sha3_256, u64>::import_state(&drbg.export_state())
+            .expect("Should import SC_DRBG state");
+    assert_eq!(restored.commitment_mode(), CommitmentMode::Merkle);
+    for expected in &u32_merkle[2..5] {
+        assert_eq!(restored.next_u32(), *expected);
+    }
+    // Check that the flat commitment produces different output
+    let mut flat = builder
+        .commitment(CommitmentMode::Flat)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    assert_ne!(flat.commitment_root(), root);
+    assert_ne!(flat.next_u32(), u32_merkle[0]);
+    // Check that leaves are blinded by the secret state, so the sibling of
+    // element 0 is not a hash of element 1 alone, and changes with the
+    // unopened last element
+    let mut changed = arr.clone();
+    changed[arr.len() - 1][0] ^= 0x01;
+    let proof = |arr: &[Vec<u8>]| {
+        Drbg::<Sha3_256, u64>::builder()
+            .array(arr)
+            .commitment(CommitmentMode::Merkle)
+            .init(false)
+            .build()
+            .expect("Should create new SC_DRBG instance")
+            .prove_element(0)
+            .expect("Should prove element")
+    };
+    assert_ne!(proof(&arr).siblings[0], proof(&changed).siblings[0]);
+    // Check proofs for every array length, including unbalanced trees
+    for len in 1..=arr.len() {
+        let drbg = Drbg::<Sha3_256, u32>::builder()
+            .array(&arr[..len])
+            .commitment(CommitmentMode::Merkle)
+            .init(false)
+            .build()
+            .expect("Should create new SC_DRBG instance");
+        let root = drbg.commitment_root();
+        for (i, element) in arr[..len].iter().enumerate() {
+            let proof = drbg.prove_element(i).expect("Should prove element");
+            assert!(verify_element_proof::<Sha3_256, u32>(
+                &root, element, &proof
+            ));
+            let mut resized = proof.clone();
+            resized.count += 1;
+            assert!(!verify_element_proof::<Sha3_256, u32>(
+                &root, element, &resized
+            ));
+        }
+    }
+}
+
+#[test]
+fn drbg_merkle_errors() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Check that proofs require the Merkle commitment mode
+    let drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(drbg.commitment_mode(), CommitmentMode::Flat);
+    assert_eq!(drbg.prove_element(0), Err(DrbgError::ProofUnavailable));
+    // Check that out of range indices are rejected
+    let drbg = Drbg::<Sha3_256, u32>::builder()
+        .array(&arr)
+        .commitment(CommitmentMode::Merkle)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(
+        drbg.prove_element(arr.len()),
+        Err(DrbgError::IndexOutOfRange(vec![arr.len()]))
+    );
+    // Check that an unknown commitment mode is rejected
+    let mut state = drbg.export_state().to_vec();
+    state[STATE_CTR_OFFSET + 4] = 0xFF;
+    let result = Drbg::<Sha3_256, u32>::import_state(&state);
+    assert_eq!(result.err(), Some(DrbgError::InvalidState));
+    // Check that a snapshot with no elements is rejected, rather than
+    // reaching the Merkle commitment of an empty array. The element count
+    // follows the counter, modes, empty context string, and PRK
+    let mut state = drbg.export_state().to_vec();
+    state.truncate(STATE_CTR_OFFSET + 4 + 2 + 4 + 4 + 32);
+    state.extend_from_slice(&[0u8; 4]);
+    let result = Drbg::<Sha3_256, u32>::import_state(&state);
+    assert_eq!(result.err(), Some(DrbgError::EmptyArray));
+}
+
+#[test]
+fn drbg_audited() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let builder = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr)
+        .context(Some(context))
+        .endian(Endian::BigEndian);
+    // Check that outputs match those of an unaudited DRBG
+    let mut drbg = builder.build().expect("Should create new SC_DRBG instance");
+    let mut audited =
+        AuditedDrbg::new(&builder).expect("Should create new SC_DRBG instance");
+    let root = drbg.commitment_root();
+    assert_eq!(audited.next_u32_subset(3), drbg.next_u32_subset(3));
+    let (mut expected, mut found) = ([0u8; 32], [0u8; 32]);
+    drbg.fill_bytes_with_elements(&[6, 0, 3], &mut expected)
+        .expect("Should fill bytes");
+    audited
+        .fill_bytes_with_elements(&[6, 0, 3], &mut found)
+        .expect("Should fill bytes");
+    assert_eq!(found, expected);
+    let (mut expected, mut found) = ([0u8; 100], [0u8; 100]);
+    drbg.fill_bytes_subset(20, &mut expected);
+    audited.fill_bytes_subset(20, &mut found);
+    assert_eq!(found, expected);
+    assert_eq!(audited.next_u64(), drbg.next_u64());
+    // Check the recorded entries
+    let transcript = audited.transcript();
+    assert_eq!(transcript.len(), 4);
+    assert_eq!(transcript[0].commit_before, root);
+    for (i, entry) in transcript.iter().enumerate() {
+        assert_eq!(entry.counter, i as u128);
+        assert_eq!(entry.subset, entry.indices.len());
+        assert_eq!(entry.explicit, i == 1);
+        if let Some(next) = transcript.get(i + 1) {
+            assert_eq!(entry.commit_after, next.commit_before);
+            assert_ne!(entry.chain, next.chain);
+        }
+    }
+    assert_eq!(
+        transcript
+            .iter()
+            .map(|e| (e.subset, e.len))
+            .collect::<Vec<_>>(),
+        [(3, 4), (3, 32), (7, 100), (7, 8)]
+    );
+    assert_eq!(transcript[1].indices, [6, 0, 3]);
+    assert_eq!(audited.drbg().commitment_root(), drbg.commitment_root());
+    // Check that the recorded indices are those selected by the PRF
+    let mut expected = [0u8; 4];
+    let mut replay =
+        builder.build().expect("Should create new SC_DRBG instance");
+    replay
+        .fill_bytes_with_elements(&transcript[0].indices, &mut expected)
+        .expect("Should fill bytes");
+    let mut drbg = builder.build().expect("Should create new SC_DRBG instance");
+    assert_eq!(drbg.next_u32_subset(3), u32::from_be_bytes(expected));
+    // Check that the transcript and its prefixes are verified
+    let transcript = audited.into_transcript();
+    for len in 0..=transcript.len() {
+        assert_eq!(verify_transcript(&builder, &transcript[..len]), Ok(()));
+    }
+}
+
+#[test]
+fn drbg_audited_errors() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let builder = Drbg::<Sha3_256, u32>::builder()
+        .array(&arr)
+        .context(Some(context));
+    let mut audited =
+        AuditedDrbg::new(&builder).expect("Should create new SC_DRBG instance");
+    for subset in 1..=4 {
+        audited.next_u32_subset(subset);
+    }
+    let transcript = audited.into_transcript();
+    let mismatch = |entry| Err(DrbgError::TranscriptMismatch { entry });
+    // Check that modified entries are rejected
+    let mut modified = transcript.clone();
+    modified[2].output_hash[0] ^= 0x01;
+    assert_eq!(verify_transcript(&builder, &modified), mismatch(2));
+    let mut modified = transcript.clone();
+    modified[1].indices.swap(0, 1);
+    assert_eq!(verify_transcript(&builder, &modified), mismatch(1));
+    let mut modified = transcript.clone();
+    modified[3].explicit = true;
+    assert_eq!(verify_transcript(&builder, &modified), mismatch(3));
+    let mut modified = transcript.clone();
+    modified[0].indices = vec![arr.len()];
+    modified[0].explicit = true;
+    assert_eq!(verify_transcript(&builder, &modified), mismatch(0));
+    // Check that reordered and removed entries are rejected
+    let mut modified = transcript.clone();
+    modified.swap(1, 2);
+    assert_eq!(verify_transcript(&builder, &modified), mismatch(1));
+    assert_eq!(verify_transcript(&builder, &transcript[1..]), mismatch(0));
+    // Check that a different configuration is rejected
+    let other = builder.context(Some("other-app"));
+    assert_eq!(verify_transcript(&other, &transcript), mismatch(0));
+    let empty = Drbg::<Sha3_256, u32>::builder();
+    assert_eq!(
+        verify_transcript(&empty, &transcript),
+        Err(DrbgError::EmptyArray)
+    );
+    assert!(matches!(
+        AuditedDrbg::new(&empty),
+        Err(DrbgError::EmptyArray)
+    ));
+}
+
+#[test]
+fn drbg_introspection() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let mut drbg = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr[..3])
+        .context(Some(context))
+        .endian(Endian::BigEndian)
+        .init(false)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    // Check accessors before and after output and reseeding
+    assert_eq!(drbg.counter(), 0);
+    assert_eq!(drbg.element_count(), 3);
+    assert_eq!(drbg.context(), context);
+    assert_eq!(drbg.endian(), Endian::BigEndian);
+    drbg.next_u64();
+    drbg.fill_bytes_subset(2, &mut [0u8; 16]);
+    assert_eq!(drbg.counter(), 2);
+    drbg.reseed(&arr[3..], None).expect("Should reseed");
+    assert_eq!(drbg.counter(), 0);
+    assert_eq!(drbg.element_count(), 3);
+    // Check that Debug shows the configuration, with secrets redacted
+    drbg.next_u32();
+    assert_eq!(
+        format!("{:?}", drbg),
+        "Drbg { digest: SHA3-256, counter_width: u64, backend: AES-CTR, \
+         endian: BigEndian, commitment: Flat, evolution: Sequential, \
+         element_count: 3, element_lens: [16, 16, 16], \
+         context: \"some-test-app\", counter: 1, arr: <redacted>, \
+         prk: <redacted>, .. }"
+    );
+    let drbg = Drbg::<Sha3_256, u32>::new_le(&arr, None, false)
+        .expect("Should create new SC_DRBG instance");
+    assert_eq!(drbg.context(), "");
+    let debug = format!("{:?}", drbg);
+    assert!(debug.contains("counter_width: u32"));
+    assert!(debug.starts_with("Drbg { digest: SHA3-256,"));
+    // Check that SHA-2 digests are shown with their standard names
+    let sha2_names = [
+        format!("{:?}", Drbg::<Sha224, u32>::new_le(&arr, None, false)),
+        format!("{:?}", Drbg::<Sha256, u32>::new_le(&arr, None, false)),
+        format!("{:?}", Drbg::<Sha384, u32>::new_le(&arr, None, false)),
+        format!("{:?}", Drbg::<Sha512, u32>::new_le(&arr, None, false)),
+        format!("{:?}", Drbg::<Sha512_256, u32>::new_le(&arr, None, false)),
+    ];
+    for (debug, name) in sha2_names.iter().zip([
+        "SHA-224",
+        "SHA-256",
+        "SHA-384",
+        "SHA-512",
+        "SHA-512/256",
+    ]) {
+        assert!(debug.starts_with(&format!("Ok(Drbg {{ digest: {},", name)));
+    }
+    assert!(debug.contains("context: \"\""));
+    for element in &arr {
+        assert!(!debug.contains(&format!("{:?}", element)));
+    }
+}
+
+#[test]
+fn drbg_fork() {
+    // Expected outputs of a child, and of the parent after forking
+    let child_u64: [u64; 3] =
+        [451995015735309227, 618368823508167391, 12190545604581583604];
+    let parent_u64: [u64; 3] = [
+        17251737185066698826,
+        13763800745039406841,
+        12537566851041340744,
+    ];
+    let split_u64: [u64; 3] = [
+        11122013858613022500,
+        8612973042293103379,
+        5075630943971354076,
+    ];
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let new_drbg = || {
+        Drbg::<Sha3_256, u64>::new_le(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance")
+    };
+    // Check child and parent outputs against known answers
+    let mut drbg = new_drbg();
+    let mut child = drbg.fork("worker-0");
+    assert_eq!(child.counter(), 0);
+    assert_eq!(child.context(), context);
+    assert_eq!(child.element_count(), arr.len());
+    for expected in child_u64 {
+        assert_eq!(child.next_u64(), expected);
+    }
+    for expected in parent_u64 {
+        assert_eq!(drbg.next_u64(), expected);
+    }
+    // Check that forking advances the parent, and resets its counter
+    assert_ne!(new_drbg().next_u64(), parent_u64[0]);
+    let mut drbg = new_drbg();
+    drbg.next_u64();
+    drbg.fork("worker-0");
+    assert_eq!(drbg.counter(), 0);
+    // Check that forks are reproducible, and differ by label and by fork
+    let mut drbg = new_drbg();
+    let mut same = drbg.fork("worker-0");
+    assert_eq!(same.next_u64(), child_u64[0]);
+    let mut again = drbg.fork("worker-0");
+    assert_ne!(again.next_u64(), child_u64[0]);
+    let mut other = new_drbg().fork("worker-1");
+    assert_ne!(other.next_u64(), child_u64[0]);
+    // Check that a split matches forks labelled by index
+    let mut drbg = new_drbg();
+    let mut children = drbg.split_n(3);
+    assert_eq!(children.len(), 3);
+    for (i, child) in children.iter_mut().enumerate() {
+        assert_eq!(child.next_u64(), split_u64[i]);
+    }
+    let mut forked = new_drbg();
+    for (i, expected) in split_u64.into_iter().enumerate() {
+        assert_eq!(forked.fork(&i.to_string()).next_u64(), expected);
+    }
+    assert_eq!(forked.next_u64(), drbg.next_u64());
+    assert!(new_drbg().split_n(0).is_empty());
+}
+
+#[test]
+fn drbg_seekable() {
+    // Expected output at counter 10000, and first output after a ratchet
+    let seek_bytes = hex!("b7e4f4ca35abdaa67af58dbc5c7e4820");
+    let ratchet_bytes = hex!(
+        "bcdaf28015e42e3dc917389db146df7d3715c99d4d799df29866f328585a39f5"
+    );
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    let builder = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr)
+        .context(Some(context))
+        .evolution(EvolutionMode::Seekable);
+    let new_drbg = || builder.build().expect("Should create DRBG");
+    // Check that the first output matches the sequential mode
+    let mut drbg = new_drbg();
+    assert_eq!(drbg.evolution_mode(), EvolutionMode::Seekable);
+    let mut sequential =
+        Drbg::<Sha3_256, u64>::new_le(&arr, Some(context), true)
+            .expect("Should create new SC_DRBG instance");
+    assert_eq!(sequential.evolution_mode(), EvolutionMode::Sequential);
+    assert_eq!(drbg.next_u64(), sequential.next_u64());
+    // Check that seeks match sequential outputs, without changing the state
+    let seeker = new_drbg();
+    let mut drbg = new_drbg();
+    for subset in 1..=arr.len() {
+        let counter = drbg.counter();
+        let mut bytes = [0u8; 40];
+        let mut seek = [0u8; 40];
+        drbg.fill_bytes_subset(subset, &mut bytes);
+        seeker
+            .output_at(counter, subset, &mut seek)
+            .expect("Should seek output");
+        assert_eq!(bytes, seek);
+    }
+    let mut seek = [0u8; 8];
+    seeker
+        .output_at(drbg.counter(), arr.len(), &mut seek)
+        .expect("Should seek output");
+    assert_eq!(drbg.next_u64(), u64::from_le_bytes(seek));
+    assert_eq!(seeker.counter(), 0);
+    // Check seeking ahead against a known answer
+    let mut seek = [0u8; 16];
+    seeker
+        .output_at(10000, 2, &mut seek)
+        .expect("Should seek output");
+    assert_eq!(seek, seek_bytes);
+    // Check that a ratchet starts a new sequence, and advances the state
+    let mut drbg = new_drbg();
+    drbg.next_u32();
+    drbg.ratchet();
+    assert_eq!(drbg.counter(), 0);
+    let mut seek = [0u8; 32];
+    drbg.output_at(0, arr.len(), &mut seek)
+        .expect("Should seek output");
+    assert_eq!(seek, ratchet_bytes);
+    let mut bytes = [0u8; 32];
+    drbg.fill_bytes(&mut bytes);
+    assert_eq!(bytes, ratchet_bytes);
+    let mut before = [0u8; 32];
+    seeker
+        .output_at(0, arr.len(), &mut before)
+        .expect("Should seek output");
+    assert_ne!(before, ratchet_bytes);
+    // Check that additional input is bound into the output alone, leaving
+    // the state, later outputs, and ratchets as without the input
+    let mut with_input = new_drbg();
+    let mut without = new_drbg();
+    let mut bytes = [0u8; 32];
+    let mut plain = [0u8; 32];
+    with_input
+        .fill_bytes_with_input(2, b"user-1234", &mut bytes)
+        .expect("Should fill bytes");
+    without.fill_bytes_subset(2, &mut plain);
+    assert_ne!(bytes, plain);
+    let mut seek = [0u8; 32];
+    seeker
+        .output_at(0, 2, &mut seek)
+        .expect("Should seek output");
+    assert_eq!(seek, plain);
+    assert_eq!(with_input.export_state(), without.export_state());
+    assert_eq!(with_input.next_u64(), without.next_u64());
+    with_input.ratchet();
+    without.ratchet();
+    assert_eq!(with_input.export_state(), without.export_state());
+    // Check that a fork resets the parent counter, like a ratchet
+    let mut drbg = new_drbg();
+    drbg.next_u64();
+    drbg.next_u64();
+    let mut before = [0u8; 8];
+    drbg.output_at(0, arr.len(), &mut before)
+        .expect("Should seek output");
+    drbg.fork("worker-0");
+    assert_eq!(drbg.counter(), 0);
+    let mut seek = [0u8; 8];
+    drbg.output_at(0, arr.len(), &mut seek)
+        .expect("Should seek output");
+    assert_ne!(seek, before);
+    assert_eq!(drbg.next_u64(), u64::from_le_bytes(seek));
+    assert_eq!(drbg.counter(), 1);
+    // Check that the evolution mode survives snapshots and forks
+    let mut restored =
+        Drbg::<Sha3_256, u64>::import_state(&drbg.export_state())
+            .expect("Should import SC_DRBG state");
+    assert_eq!(restored.evolution_mode(), EvolutionMode::Seekable);
+    assert_eq!(restored.next_u64(), drbg.next_u64());
+    assert_eq!(
+        drbg.fork("worker-0").evolution_mode(),
+        EvolutionMode::Seekable
+    );
+}
+
+#[cfg(feature = "bench")]
+#[test]
+fn drbg_uncached_baseline() {
+    // Check that the uncached baseline used in benchmarks matches the cached
+    // output, and is only available in the seekable mode
+    let arr = get_seed_vec();
+    let drbg = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr)
+        .endian(Endian::BigEndian)
+        .commitment(CommitmentMode::Merkle)
+        .evolution(EvolutionMode::Seekable)
+        .build()
+        .expect("Should create DRBG");
+    for subset in 1..=arr.len() {
+        let mut cached = [0u8; 40];
+        let mut uncached = [0u8; 40];
+        drbg.output_at(7, subset, &mut cached)
+            .expect("Should seek output");
+        drbg.output_at_uncached(7, subset, &mut uncached)
+            .expect("Should seek output");
+        assert_eq!(cached, uncached);
+    }
+    let sequential = Drbg::<Sha3_256, u64>::new_le(&arr, None, true)
+        .expect("Should create new SC_DRBG instance");
+    let mut bytes = [0u8; 8];
+    assert_eq!(
+        sequential.output_at_uncached(0, 1, &mut bytes),
+        Err(DrbgError::SeekUnavailable)
+    );
+}
+
+#[test]
+fn drbg_seekable_errors() {
+    // Get seed elements from hex strings, set context
+    let arr = get_seed_vec();
+    let context = "some-test-app";
+    // Check that seeking requires the seekable mode
+    let mut drbg = Drbg::<Sha3_256, u32>::new_le(&arr, Some(context), true)
+        .expect("Should create new SC_DRBG instance");
+    let mut bytes = [0u8; 16];
+    assert_eq!(
+        drbg.output_at(0, 1, &mut bytes),
+        Err(DrbgError::SeekUnavailable)
+    );
+    // Check that a ratchet in the sequential mode resets the counter
+    drbg.next_u32();
+    drbg.ratchet();
+    assert_eq!(drbg.counter(), 0);
+    // Check that the maximum counter value is rejected
+    let drbg = Drbg::<Sha3_256, u32>::builder()
+        .array(&arr)
+        .evolution(EvolutionMode::Seekable)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    let exhausted = Err(DrbgError::CounterExhausted {
+        max: u32::MAX as u128,
+    });
+    assert_eq!(drbg.output_at(u32::MAX, 1, &mut bytes), exhausted);
+    assert!(drbg.output_at(u32::MAX - 1, 1, &mut bytes).is_ok());
+    // Check that an unknown evolution mode is rejected
+    let mut state = drbg.export_state().to_vec();
+    state[STATE_CTR_OFFSET + 5] = 0xFF;
+    let result = Drbg::<Sha3_256, u32>::import_state(&state);
+    assert_eq!(result.err(), Some(DrbgError::InvalidState));
+}
+
+fn quorum_holding(
+    dealer: &Drbg<Sha3_256, u64>,
+    arr: &[Vec<u8>],
+    indices: &[usize],
+) -> QuorumDrbg<Sha3_256, u64> {
+    // Create a quorum generator holding the given elements with their proofs
+    let held: Vec<(Vec<u8>, ElementProof)> = indices
+        .iter()
+        .map(|&i| {
+            let proof = dealer.prove_element(i).expect("Should prove element");
+            (arr[i].clone(), proof)
+        })
+        .collect();
+    QuorumDrbg::new(
+        &dealer.commitment_root(),
+        arr.len(),
+        2,
+        &held,
+        Some("some-test-app"),
+        Endian::BigEndian,
+    )
+    .expect("Should create new SC_DRBG instance")
+}
+
+#[test]
+fn drbg_quorum() {
+    // Expected outputs from elements 2 and 3, then elements 0 and 3
+    let quorum_bytes = [
+        hex!(
+            "e13cbec2e2f98d3a110378ce74b364672fab0ca1d466ca1faf3ef484fc1d37c0"
+        ),
+        hex!(
+            "1f62433f2d2261168bdd789cce24cc8605fa725587badd1c1e9cabd1b77f8089"
+        ),
+    ];
+    // Get seed elements from hex strings, and publish the Merkle commitment
+    // of the uninitialized seed elements
+    let arr = get_seed_vec();
+    let mut dealer = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr)
+        .context(Some("some-test-app"))
+        .endian(Endian::BigEndian)
+        .commitment(CommitmentMode::Merkle)
+        .init(false)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    // Check that parties holding different elements, or every element,
+    // generate the same outputs from the elements they share
+    let mut first = quorum_holding(&dealer, &arr, &[0, 1, 2, 3]);
+    let mut second = quorum_holding(&dealer, &arr, &[3, 2, 4, 5, 6]);
+    let mut full = quorum_holding(&dealer, &arr, &[0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(first.missing_elements(), vec![4, 5, 6]);
+    assert_eq!(second.missing_elements(), vec![0, 1]);
+    assert!(full.missing_elements().is_empty());
+    assert_eq!(first.element_count(), arr.len());
+    assert_eq!(first.threshold(), 2);
+    assert_eq!(first.commitment_root(), dealer.commitment_root());
+    let mut bytes = [0u8; 32];
+    for drbg in [&mut first, &mut second, &mut full] {
+        drbg.fill_bytes_with_elements(&[2, 3], &mut bytes)
+            .expect("Should fill bytes");
+        assert_eq!(bytes, quorum_bytes[0]);
+    }
+    for drbg in [&mut first, &mut full] {
+        drbg.fill_bytes_with_elements(&[3, 0], &mut bytes)
+            .expect("Should fill bytes");
+        assert_eq!(bytes, quorum_bytes[1]);
+        assert_eq!(drbg.counter(), 2);
+    }
+    // Check that missing elements are reported, without advancing the
+    // counter or filling the buffer
+    let mut missing = [0u8; 32];
+    assert_eq!(
+        second.fill_bytes_with_elements(&[1, 3, 0], &mut missing),
+        Err(DrbgError::MissingElements(vec![1, 0]))
+    );
+    assert_eq!(
+        second.fill_bytes_with_elements(&[4, 0], &mut missing),
+        Err(DrbgError::MissingElements(vec![0]))
+    );
+    assert_eq!(missing, [0u8; 32]);
+    assert_eq!(second.counter(), 1);
+    // Check that elements failing verification are treated as missing
+    let root = dealer.commitment_root();
+    let proof = |i| dealer.prove_element(i).expect("Should prove element");
+    let mut moved = proof(1);
+    moved.index = 2;
+    let mut reordered = proof(3);
+    reordered.endian = Endian::LittleEndian;
+    let held = vec![
+        (arr[0].clone(), proof(0)),
+        (arr[2].clone(), proof(1)),
+        (arr[1].clone(), moved),
+        (arr[3].clone(), reordered),
+        (Vec::new(), proof(4)),
+    ];
+    let mut drbg = QuorumDrbg::<Sha3_256, u64>::new(
+        &root,
+        arr.len(),
+        1,
+        &held,
+        Some("some-test-app"),
+        Endian::BigEndian,
+    )
+    .expect("Should create new SC_DRBG instance");
+    assert_eq!(drbg.missing_elements(), vec![1, 2, 3, 4, 5, 6]);
+    assert!(drbg.fill_bytes_with_elements(&[0], &mut bytes).is_ok());
+    // Check that the output is bound to the context string and commitment
+    let held = vec![(arr[2].clone(), proof(2)), (arr[3].clone(), proof(3))];
+    let mut other = QuorumDrbg::<Sha3_256, u64>::new(
+        &root,
+        arr.len(),
+        2,
+        &held,
+        None,
+        Endian::BigEndian,
+    )
+    .expect("Should create new SC_DRBG instance");
+    other
+        .fill_bytes_with_elements(&[2, 3], &mut bytes)
+        .expect("Should fill bytes");
+    assert_ne!(bytes, quorum_bytes[0]);
+    // Check that the seed elements are not verified against the commitment
+    // of a later state
+    dealer.next_u32();
+    let later = quorum_holding(&dealer, &arr, &[2, 3]);
+    assert_eq!(later.missing_elements(), (0..arr.len()).collect::<Vec<_>>());
+}
+
+#[test]
+fn drbg_quorum_errors() {
+    // Get seed elements from hex strings, and publish the Merkle commitment
+    let arr = get_seed_vec();
+    let dealer = Drbg::<Sha3_256, u64>::builder()
+        .array(&arr)
+        .commitment(CommitmentMode::Merkle)
+        .init(false)
+        .build()
+        .expect("Should create new SC_DRBG instance");
+    let root = dealer.commitment_root();
+    let proof = |i| dealer.prove_element(i).expect("Should prove element");
+    let new_quorum = |root: &[u8], count, held: &[(Vec<u8>, ElementProof)]| {
+        QuorumDrbg::<Sha3_256, u64>::new(
+            root,
+            count,
+            count.min(2),
+            held,
+            None,
+            Endian::LittleEndian,
+        )
+        .err()
+    };
+    // Check that an empty array and malformed commitments are rejected
+    assert_eq!(new_quorum(&root, 0, &[]), Some(DrbgError::EmptyArray));
+    assert_eq!(
+        new_quorum(&root[..16], arr.len(), &[]),
+        Some(DrbgError::InvalidCommitment {
+            expected: 32,
+            found: 16
+        })
+    );
+    // Check that a threshold of zero or above the element count is rejected
+    for threshold in [0, arr.len() + 1] {
+        let result = QuorumDrbg::<Sha3_256, u64>::new(
+            &root,
+            arr.len(),
+            threshold,
+            &[],
+            None,
+            Endian::LittleEndian,
+        );
+        assert_eq!(
+            result.err(),
+            Some(DrbgError::InvalidThreshold {
+                threshold,
+                count: arr.len()
+            })
+        );
+    }
+    // Check that invalid proof indices are rejected
+    let mut out_of_range = proof(0);
+    out_of_range.index = arr.len();
+    assert_eq!(
+        new_quorum(&root, arr.len(), &[(arr[0].clone(), out_of_range)]),
+        Some(DrbgError::IndexOutOfRange(vec![arr.len()]))
+    );
+    let held = [(arr[1].clone(), proof(1)), (arr[1].clone(), proof(1))];
+    assert_eq!(
+        new_quorum(&root, arr.len(), &held),
+        Some(DrbgError::DuplicateIndex(vec![1]))
+    );
+    // Check that a generator holding no elements can be created, but not
+    // generate output
+    let mut drbg = QuorumDrbg::<Sha3_256, u64>::new(
+        &root,
+        arr.len(),
+        2,
+        &[],
+        None,
+        Endian::LittleEndian,
+    )
+    .expect("Should create new SC_DRBG instance");
+    assert_eq!(drbg.missing_elements(), (0..arr.len()).collect::<Vec<_>>());
+    let mut bytes = [0u8; 16];
+    assert_eq!(
+        drbg.fill_bytes_with_elements(&[0, arr.len()], &mut bytes),
+        Err(DrbgError::IndexOutOfRange(vec![arr.len()]))
+    );
+    assert_eq!(
+        drbg.fill_bytes_with_elements(&[5, 5], &mut bytes),
+        Err(DrbgError::DuplicateIndex(vec![5]))
+    );
+    assert_eq!(
+        drbg.fill_bytes_with_elements(&[6, 5], &mut bytes),
+        Err(DrbgError::MissingElements(vec![6, 5]))
+    );
+    // Check that an empty selection, or one below the threshold, is
+    // rejected even when every selected element is held
+    let held: Vec<_> =
+        (0..arr.len()).map(|i| (arr[i].clone(), proof(i))).collect();
+    let mut full = QuorumDrbg::<Sha3_256, u64>::new(
+        &root,
+        arr.len(),
+        2,
+        &held,
+        None,
+        Endian::LittleEndian,
+    )
+    .expect("Should create new SC_DRBG instance");
+    assert_eq!(
+        full.fill_bytes_with_elements(&[], &mut bytes),
+        Err(DrbgError::EmptySelection)
+    );
+    assert_eq!(
+        full.fill_bytes_with_elements(&[3], &mut bytes),
+        Err(DrbgError::BelowThreshold {
+            threshold: 2,
+            found: 1
+        })
+    );
+    assert_eq!(bytes, [0u8; 16]);
+    assert_eq!(full.counter(), 0);
+    assert_eq!(drbg.counter(), 0);
+}
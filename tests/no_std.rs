@@ -0,0 +1,50 @@
+use std::{path::Path, process::Command};
+
+const TARGET: &str = "thumbv7em-none-eabi";
+
+fn target_installed() -> bool {
+    // Look for the standard library of the target in the sysroot
+    let output = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .expect("Should run rustc");
+    let sysroot = String::from_utf8_lossy(&output.stdout);
+    Path::new(sysroot.trim())
+        .join("lib/rustlib")
+        .join(TARGET)
+        .exists()
+}
+
+fn build_no_std(features: &[&str]) {
+    // Build the library for a bare metal target, without the std feature
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(manifest_dir)
+        .args([
+            "build",
+            "--lib",
+            "--no-default-features",
+            "--target",
+            TARGET,
+        ])
+        .arg(format!("--features={}", features.join(",")))
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(manifest_dir).join("target/no_std"),
+        )
+        .status()
+        .expect("Should run cargo");
+    assert!(status.success(), "no_std build failed for {}", TARGET);
+}
+
+#[test]
+fn drbg_no_std() {
+    // Skip when the bare metal target is not installed
+    if !target_installed() {
+        eprintln!("skipping: rustup target add {} to run", TARGET);
+        return;
+    }
+    build_no_std(&[]);
+    build_no_std(&["serde"]);
+    build_no_std(&["dyn"]);
+}
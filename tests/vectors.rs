@@ -0,0 +1,143 @@
+#![cfg(feature = "vectors")]
+
+use sc_drbg::{Call, DrbgError, VectorFile};
+use std::{collections::HashMap, fs, path::Path};
+
+fn load_vectors() -> Vec<(String, VectorFile)> {
+    // Load every JSON vector file under tests/vectors
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/vectors");
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .expect("Should read vector directory")
+        .map(|entry| entry.expect("Should read directory entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+        .into_iter()
+        .map(|path| {
+            let json = fs::read_to_string(&path).expect("Should read file");
+            let vectors =
+                VectorFile::from_json(&json).expect("Should parse vectors");
+            (path.display().to_string(), vectors)
+        })
+        .collect()
+}
+
+#[test]
+fn vectors_conformance() {
+    // Check that every vector file matches the output of this crate
+    let files = load_vectors();
+    assert!(!files.is_empty());
+    for (path, vectors) in files {
+        assert!(!vectors.vectors.is_empty());
+        if let Err(err) = vectors.check() {
+            panic!("{}: {}", path, err);
+        }
+    }
+}
+
+#[test]
+fn vectors_match_known_answers() {
+    // Check that the SHA3-256 vectors agree with the hard-coded outputs of
+    // tests/main.rs
+    let (_, vectors) = load_vectors()
+        .into_iter()
+        .find(|(path, _)| path.ends_with("sha3.json"))
+        .expect("Should find SHA-3 vectors");
+    let vector = vectors
+        .vectors
+        .iter()
+        .find(|vector| vector.name == "sha3-256-u64-le")
+        .expect("Should find vector");
+    assert_eq!(
+        vector.calls[0],
+        Call::NextU32 {
+            subset: None,
+            expected: format!("{:08x}", 3513012354u32),
+        }
+    );
+}
+
+#[test]
+fn vectors_seek_matches_sequential() {
+    // Check that every seek in a seekable vector that repeats an earlier
+    // sequential call has the same expected output
+    let mut links = 0;
+    for (_, vectors) in load_vectors() {
+        for vector in vectors.vectors.iter().filter(|vector| vector.seekable) {
+            let mut counter = 0;
+            let mut outputs = HashMap::new();
+            for call in &vector.calls {
+                match call {
+                    Call::FillBytes {
+                        subset,
+                        len,
+                        expected,
+                    } => {
+                        outputs.insert((counter, *subset, *len), expected);
+                        counter += 1;
+                    }
+                    Call::OutputAt {
+                        counter,
+                        subset,
+                        len,
+                        expected,
+                    } => {
+                        if let Some(output) =
+                            outputs.get(&(*counter, *subset, *len))
+                        {
+                            assert_eq!(*output, expected);
+                            links += 1;
+                        }
+                    }
+                    Call::Ratchet | Call::Reseed { .. } => {
+                        counter = 0;
+                        outputs.clear();
+                    }
+                    Call::Fork { .. } => outputs.clear(),
+                    _ => counter += 1,
+                }
+            }
+        }
+    }
+    assert!(links > 0);
+}
+
+#[test]
+fn vectors_errors() {
+    // Check that a modified expected output is reported
+    let (_, mut vectors) = load_vectors().remove(0);
+    let vector = &mut vectors.vectors[0];
+    let name = vector.name.clone();
+    let Call::FillBytes { expected, .. } = &mut vector.calls[4] else {
+        panic!("Should be a fill_bytes call");
+    };
+    let found = expected.clone();
+    expected
+        .replace_range(0..2, if &found[0..2] == "00" { "01" } else { "00" });
+    let expected = expected.clone();
+    assert_eq!(
+        vectors.check(),
+        Err(DrbgError::VectorMismatch {
+            name,
+            call: 4,
+            expected,
+            found,
+        })
+    );
+    // Check that malformed vectors are rejected
+    let vector = &mut vectors.vectors[0];
+    vector.seed[0] = "not-hex".to_string();
+    assert!(matches!(vector.check(), Err(DrbgError::InvalidVector(_))));
+    vector.seed[0] = "+f".repeat(16);
+    assert!(matches!(vector.check(), Err(DrbgError::InvalidVector(_))));
+    vector.digest = "MD5".to_string();
+    assert!(matches!(
+        vector.check(),
+        Err(DrbgError::InvalidAlgorithm(_))
+    ));
+    assert!(matches!(
+        VectorFile::from_json("{\"vectors\": 1}"),
+        Err(DrbgError::InvalidVector(_))
+    ));
+}
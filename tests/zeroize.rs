@@ -0,0 +1,214 @@
+use rand_core::RngCore;
+use sc_drbg::{
+    CommitmentMode, Drbg, DrbgBuilder, QuorumDrbg, SeededDrbg, StaticDrbg,
+};
+use sha3::{Digest, Sha3_256};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    slice,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use zeroize::ZeroizeOnDrop;
+
+struct RecordingAlloc;
+
+// Only record buffers freed by the test thread, not the test harness
+thread_local! {
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+}
+// Contents of freed buffers, in storage reserved up front so that recording
+// never allocates
+static FREED: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+static OVERFLOW: AtomicBool = AtomicBool::new(false);
+const FREED_CAPACITY: usize = 1 << 24;
+
+unsafe impl GlobalAlloc for RecordingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if RECORDING.with(Cell::get) {
+            let bytes = unsafe { slice::from_raw_parts(ptr, layout.size()) };
+            let mut freed = FREED.lock().unwrap_or_else(|err| err.into_inner());
+            if freed.len() + bytes.len() <= freed.capacity() {
+                freed.extend_from_slice(bytes);
+            } else {
+                OVERFLOW.store(true, Ordering::SeqCst);
+            }
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: RecordingAlloc = RecordingAlloc;
+
+fn record_freed(f: impl FnOnce()) -> Vec<u8> {
+    {
+        let mut freed = FREED.lock().unwrap_or_else(|err| err.into_inner());
+        freed.clear();
+        freed.reserve(FREED_CAPACITY);
+    }
+    OVERFLOW.store(false, Ordering::SeqCst);
+    RECORDING.with(|recording| recording.set(true));
+    f();
+    RECORDING.with(|recording| recording.set(false));
+    assert!(!OVERFLOW.load(Ordering::SeqCst), "Freed buffers overflowed");
+    FREED.lock().unwrap_or_else(|err| err.into_inner()).clone()
+}
+
+fn assert_wiped(freed: &[u8], secrets: &[Vec<u8>]) {
+    for secret in secrets {
+        assert!(
+            !freed
+                .windows(secret.len())
+                .any(|window| window == &secret[..]),
+            "Freed buffer was not wiped"
+        );
+    }
+}
+
+fn state_secrets(drbg: &Drbg<Sha3_256, u64>) -> Vec<Vec<u8>> {
+    // Read the PRK and elements from a state snapshot
+    let state = drbg.export_state();
+    let mut rest = &state[6 + 1 + 4 + 8..];
+    let mut take = |len: usize| {
+        let (head, tail) = rest.split_at(len);
+        rest = tail;
+        head.to_vec()
+    };
+    let len = |bytes: Vec<u8>| {
+        u32::from_be_bytes(bytes.try_into().expect("Should be 4 bytes"))
+            as usize
+    };
+    let backend_len = len(take(4));
+    take(backend_len + 1 + 1 + 8 + 2);
+    let context_len = len(take(4));
+    take(context_len);
+    let prk_len = len(take(4));
+    let mut secrets = vec![take(prk_len)];
+    for _ in 0..len(take(4)) {
+        let element_len = len(take(4));
+        secrets.push(take(element_len));
+    }
+    secrets
+}
+
+type Step<'a> = Box<dyn Fn(&mut Drbg<Sha3_256, u64>) + 'a>;
+
+fn get_seed_vec() -> Vec<Vec<u8>> {
+    (0..7u8).map(|i| [0xA0 + i; 16].to_vec()).collect()
+}
+
+#[test]
+fn drbg_zeroize_freed() {
+    let arr = get_seed_vec();
+    let additional = vec![[0xB0u8; 24].to_vec(), [0xB1u8; 24].to_vec()];
+    for init in [false, true] {
+        for commitment in [CommitmentMode::Flat, CommitmentMode::Merkle] {
+            let builder = Drbg::<Sha3_256, u64>::builder()
+                .array(&arr)
+                .context(Some("some-test-app"))
+                .commitment(commitment)
+                .init(init);
+            // Check that creating the DRBG wipes copies of the seed material,
+            // the default nonce, and the initialized state
+            let mut drbg = None;
+            let freed = record_freed(|| {
+                drbg = Some(builder.build().expect("Should create DRBG"));
+            });
+            let mut drbg = drbg.expect("Should create DRBG");
+            let nonce = Sha3_256::digest(arr.concat()).to_vec();
+            assert_wiped(&freed, &arr);
+            assert_wiped(&freed, &[nonce]);
+            assert_wiped(&freed, &state_secrets(&drbg));
+            // Check that every state transition wipes the previous state
+            let mut steps: Vec<Step> = vec![
+                Box::new(|drbg| drbg.fill_bytes_subset(3, &mut [0u8; 64])),
+                Box::new(|drbg| {
+                    drbg.fill_bytes_with_elements(&[6, 0], &mut [0u8; 16])
+                        .expect("Should fill bytes");
+                }),
+                Box::new(|drbg| {
+                    drbg.fill_bytes_with_input(4, b"user-1001", &mut [0u8; 8])
+                        .expect("Should fill bytes");
+                }),
+                Box::new(|drbg| {
+                    drbg.reseed(&additional, Some("epoch-1"))
+                        .expect("Should reseed");
+                }),
+                Box::new(|drbg| {
+                    drbg.stream(32).take(100).for_each(drop);
+                }),
+                Box::new(|drbg| drbg.ratchet()),
+                Box::new(|drbg| {
+                    let state = drbg.export_state();
+                    *drbg = Drbg::import_state(&state).expect("Should import");
+                }),
+            ];
+            if commitment == CommitmentMode::Merkle {
+                steps.push(Box::new(|drbg| {
+                    drbg.commitment_root();
+                    drbg.prove_element(2).expect("Should prove element");
+                }));
+            }
+            for step in &steps {
+                let before = state_secrets(&drbg);
+                let freed = record_freed(|| step(&mut drbg));
+                assert_wiped(&freed, &before);
+                assert_wiped(&freed, &state_secrets(&drbg));
+                assert_wiped(&freed, &additional);
+            }
+            // Check that dropping the DRBG and builder wipes their state
+            let before = state_secrets(&drbg);
+            let freed = record_freed(|| {
+                drop(drbg);
+                drop(builder);
+            });
+            assert_wiped(&freed, &before);
+            assert_wiped(&freed, &arr);
+        }
+    }
+}
+
+#[test]
+fn drbg_zeroize_on_drop() {
+    // Check that generators and builders are marked as zeroized on drop
+    fn assert_zeroize_on_drop<Z: ZeroizeOnDrop>() {}
+    assert_zeroize_on_drop::<Drbg<Sha3_256, u64>>();
+    assert_zeroize_on_drop::<DrbgBuilder<Sha3_256, u64>>();
+    assert_zeroize_on_drop::<StaticDrbg<Sha3_256, u64, 4, 32>>();
+    assert_zeroize_on_drop::<QuorumDrbg<Sha3_256, u64>>();
+    assert_zeroize_on_drop::<SeededDrbg<Sha3_256, u64, 4>>();
+    // Check that a generator used as an RngCore wipes its state when dropped
+    let arr = get_seed_vec();
+    let mut drbg = Drbg::<Sha3_256, u64>::new_be(&arr, None, true)
+        .expect("Should create new SC_DRBG instance");
+    drbg.next_u64();
+    let before = state_secrets(&drbg);
+    let freed = record_freed(|| drop(drbg));
+    assert_wiped(&freed, &before);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn drbg_zeroize_deserialize() {
+    // Check that deserializing a snapshot from a sequence of bytes wipes the
+    // buffers it grows through
+    let arr = get_seed_vec();
+    let drbg = Drbg::<Sha3_256, u64>::new_le(&arr, None, true)
+        .expect("Should create new SC_DRBG instance");
+    let json = serde_json::to_string(&drbg).expect("Should serialize");
+    let mut restored = None;
+    let freed = record_freed(|| {
+        restored = Some(
+            serde_json::from_str::<Drbg<Sha3_256, u64>>(&json)
+                .expect("Should deserialize"),
+        );
+    });
+    assert_wiped(&freed, &state_secrets(&drbg));
+}